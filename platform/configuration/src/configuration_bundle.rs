@@ -0,0 +1,398 @@
+use anyhow::Result;
+use platform_core::context_v2::Context;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+use crate::{
+    configuration_diagnostic::ConfigurationDiagnostic,
+    configuration_key::ConfigurationKey,
+    configuration_model::{Configuration, ConfigurationDifference, ConfigurationModel},
+    configuration_parser::ConfigurationParser,
+    configuration_registry::ConfigurationRegistry,
+};
+
+const BUNDLE_VERSION: u32 = 1;
+
+/// Settings exported from one machine or profile to be imported in another one.
+/// The settings have the shape of a settings file, override values are nested under `[ident]` keys.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigurationBundle {
+    pub version: u32,
+    pub settings: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportSource {
+    /// Values resolved from all configuration layers.
+    Effective,
+    /// Values set in the user settings only.
+    User,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub source: ExportSource,
+    /// Leaves out values equal to the default ones.
+    pub strip_defaults: bool,
+    /// Includes the values of the settings marked as `secret` in the schema.
+    pub include_secrets: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            source: ExportSource::User,
+            strip_defaults: true,
+            include_secrets: false,
+        }
+    }
+}
+
+impl ConfigurationBundle {
+    pub fn export(
+        configuration: &Configuration,
+        registry: &ConfigurationRegistry,
+        options: &ExportOptions,
+    ) -> Self {
+        let model = match options.source {
+            ExportSource::Effective => configuration.get_consolidated_configuration(),
+            ExportSource::User => configuration.get_user_configuration(),
+        };
+        let default_model = configuration.get_default_configuration();
+
+        let mut keys: Vec<&ConfigurationKey> = model.keys().collect();
+        keys.sort();
+
+        let mut settings = Map::new();
+        for key in keys {
+            let Some(value) = model.get(key) else {
+                continue;
+            };
+
+            let is_secret = registry
+                .get_property(&key.name)
+                .is_some_and(|property| property.schema.secret);
+            if is_secret && !options.include_secrets {
+                continue;
+            }
+
+            // Override values without a default of their own are compared with the default of the setting.
+            let default_value = default_model
+                .get(key)
+                .or_else(|| default_model.get(&key.base()));
+            if options.strip_defaults && default_value == Some(value) {
+                continue;
+            }
+
+            match &key.override_ident {
+                Some(ident) => {
+                    let override_settings = settings
+                        .entry(format!("[{}]", ident))
+                        .or_insert_with(|| Value::Object(Map::new()));
+                    if let Value::Object(override_settings) = override_settings {
+                        override_settings.insert(key.name.clone(), value.clone());
+                    }
+                }
+                None => {
+                    settings.insert(key.name.clone(), value.clone());
+                }
+            }
+        }
+
+        Self {
+            version: BUNDLE_VERSION,
+            settings,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(content: &str) -> Result<Self> {
+        let bundle: Self = serde_json::from_str(content)?;
+        if bundle.version > BUNDLE_VERSION {
+            return Err(anyhow!(
+                "settings bundle version {} is not supported",
+                bundle.version
+            ));
+        }
+
+        Ok(bundle)
+    }
+}
+
+/// A setting that has a different value in the current settings and in the imported ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportConflict {
+    pub key: ConfigurationKey,
+    pub current: Value,
+    pub incoming: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    KeepCurrent,
+    UseIncoming,
+}
+
+/// The changes an import would make to the current settings, computed before anything is written.
+/// An import never removes settings, it only adds new ones and replaces the conflicting ones.
+pub struct ImportPreview {
+    current: Arc<ConfigurationModel>,
+    incoming: Arc<ConfigurationModel>,
+    difference: ConfigurationDifference,
+    conflicts: Vec<ImportConflict>,
+}
+
+impl ImportPreview {
+    /// Reads the bundle with the parser of the settings it is imported into,
+    /// so settings that can't be set there are reported through `diagnostics`.
+    pub fn new(
+        ctx: &mut Context,
+        parser: &ConfigurationParser,
+        bundle: &ConfigurationBundle,
+        current: Arc<ConfigurationModel>,
+    ) -> Result<Self> {
+        let content = serde_json::to_string(&bundle.settings)?;
+        let incoming = Arc::new(parser.parse(ctx, &content));
+
+        let merged = Arc::new(current.merge(&[Arc::clone(&incoming)]));
+        let mut difference = Configuration::compare(Arc::clone(&current), merged);
+        difference.added.sort();
+        difference.modified.sort();
+
+        let conflicts = difference
+            .modified
+            .iter()
+            .filter_map(|key| {
+                Some(ImportConflict {
+                    key: key.clone(),
+                    current: current.get(key)?.clone(),
+                    incoming: incoming.get(key)?.clone(),
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            current,
+            incoming,
+            difference,
+            conflicts,
+        })
+    }
+
+    pub fn difference(&self) -> &ConfigurationDifference {
+        &self.difference
+    }
+
+    pub fn conflicts(&self) -> &[ImportConflict] {
+        &self.conflicts
+    }
+
+    /// Problems found in the imported settings, e.g. unknown settings.
+    pub fn diagnostics(&self) -> &Vec<ConfigurationDiagnostic> {
+        self.incoming.diagnostics()
+    }
+
+    /// Returns the values to write, resolving every conflict with `resolve`.
+    pub fn resolve(
+        &self,
+        resolve: impl Fn(&ImportConflict) -> ConflictResolution,
+    ) -> Vec<(ConfigurationKey, Value)> {
+        let mut changes = Vec::new();
+
+        for key in &self.difference.added {
+            if let Some(value) = self.incoming.get(key) {
+                changes.push((key.clone(), value.clone()));
+            }
+        }
+
+        for conflict in &self.conflicts {
+            if resolve(conflict) == ConflictResolution::UseIncoming {
+                changes.push((conflict.key.clone(), conflict.incoming.clone()));
+            }
+        }
+
+        changes
+    }
+
+    /// Returns the settings as they would be after the import.
+    pub fn resolved_model(
+        &self,
+        resolve: impl Fn(&ImportConflict) -> ConflictResolution,
+    ) -> ConfigurationModel {
+        let mut incoming = ConfigurationModel::empty();
+        for (key, value) in self.resolve(resolve) {
+            incoming.set_value(key, value);
+        }
+
+        self.current.merge(&[Arc::new(incoming)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attribute_name,
+        configuration_default::DefaultConfiguration,
+        configuration_model::ConfigurationTarget,
+        configuration_registry::{
+            test_utils::registry_with, ConfigurationPropertySchema, PropertyMap,
+        },
+        property_key,
+    };
+    use platform_core::context_v2::{atom::Atom, ContextCell};
+    use platform_core::platform::cross::client::CrossPlatformClient;
+    use serde_json::json;
+    use std::rc::Rc;
+
+    fn create_registry(ctx: &mut Context) -> Atom<ConfigurationRegistry> {
+        let mut properties = PropertyMap::new();
+        properties.insert(
+            property_key!(editor.fontSize),
+            ConfigurationPropertySchema {
+                default: Some(json!(12)),
+                ..Default::default()
+            },
+        );
+        properties.insert(
+            property_key!(editor.tabSize),
+            ConfigurationPropertySchema {
+                default: Some(json!(4)),
+                ..Default::default()
+            },
+        );
+        properties.insert(
+            property_key!(editor.accessToken),
+            ConfigurationPropertySchema {
+                secret: true,
+                ..Default::default()
+            },
+        );
+
+        registry_with(ctx, "editor", properties)
+    }
+
+    fn create_configuration(
+        ctx: &mut Context,
+        registry: &Atom<ConfigurationRegistry>,
+        user_model: ConfigurationModel,
+    ) -> Configuration {
+        let default_configuration = DefaultConfiguration::new(registry.clone());
+        default_configuration.initialize(ctx);
+
+        Configuration::new(
+            default_configuration.get_configuration_model().unwrap(),
+            Arc::new(ConfigurationModel::empty()),
+            Arc::new(user_model),
+            Arc::new(ConfigurationModel::empty()),
+            Arc::new(ConfigurationModel::empty()),
+        )
+    }
+
+    #[test]
+    fn test_export_strips_defaults_and_secrets() {
+        let ctx_cell = ContextCell::new(Rc::new(CrossPlatformClient::new()));
+        let ctx: &mut Context = &mut ctx_cell.borrow_mut();
+        let registry = create_registry(ctx);
+
+        let mut user_model = ConfigurationModel::empty();
+        user_model.set_value(ConfigurationKey::new("editor.fontSize"), json!(12));
+        user_model.set_value(ConfigurationKey::new("editor.tabSize"), json!(2));
+        user_model.set_value(
+            ConfigurationKey::with_override("rust", "editor.tabSize"),
+            json!(8),
+        );
+        user_model.set_value(ConfigurationKey::new("editor.accessToken"), json!("token"));
+        let configuration = create_configuration(ctx, &registry, user_model);
+
+        let bundle = ConfigurationBundle::export(
+            &configuration,
+            registry.read(ctx),
+            &ExportOptions::default(),
+        );
+        assert_eq!(
+            Value::Object(bundle.settings.clone()),
+            json!({
+                "editor.tabSize": 2,
+                "[rust]": { "editor.tabSize": 8 }
+            })
+        );
+
+        let bundle = ConfigurationBundle::export(
+            &configuration,
+            registry.read(ctx),
+            &ExportOptions {
+                source: ExportSource::Effective,
+                strip_defaults: false,
+                include_secrets: true,
+            },
+        );
+        assert_eq!(bundle.settings.get("editor.fontSize"), Some(&json!(12)));
+        assert_eq!(
+            bundle.settings.get("editor.accessToken"),
+            Some(&json!("token"))
+        );
+
+        let restored = ConfigurationBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        assert_eq!(restored, bundle);
+    }
+
+    #[test]
+    fn test_import_preview_reports_conflicts() {
+        let ctx_cell = ContextCell::new(Rc::new(CrossPlatformClient::new()));
+        let ctx: &mut Context = &mut ctx_cell.borrow_mut();
+        let registry = create_registry(ctx);
+        let parser = ConfigurationParser::new(registry).with_target(ConfigurationTarget::User);
+
+        let mut current = ConfigurationModel::empty();
+        current.set_value(ConfigurationKey::new("editor.fontSize"), json!(14));
+
+        let bundle = ConfigurationBundle {
+            version: BUNDLE_VERSION,
+            settings: serde_json::from_value(json!({
+                "editor.fontSize": 16,
+                "editor.tabSize": 2,
+                "editor.unknown": true
+            }))
+            .unwrap(),
+        };
+
+        let preview = ImportPreview::new(ctx, &parser, &bundle, Arc::new(current)).unwrap();
+        assert_eq!(
+            preview.difference().added,
+            vec![ConfigurationKey::new("editor.tabSize")]
+        );
+        assert_eq!(
+            preview.conflicts(),
+            &[ImportConflict {
+                key: ConfigurationKey::new("editor.fontSize"),
+                current: json!(14),
+                incoming: json!(16),
+            }]
+        );
+        assert_eq!(preview.diagnostics().len(), 1);
+
+        let model = preview.resolved_model(|_| ConflictResolution::KeepCurrent);
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.fontSize)),
+            Some(&json!(14))
+        );
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.tabSize)),
+            Some(&json!(2))
+        );
+
+        let changes = preview.resolve(|_| ConflictResolution::UseIncoming);
+        assert_eq!(changes.len(), 2);
+    }
+
+    #[test]
+    fn test_bundle_from_newer_version_is_rejected() {
+        let result = ConfigurationBundle::from_json(r#"{ "version": 2, "settings": {} }"#);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,329 @@
+use anyhow::{Context as AnyhowContext, Result};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    configuration_diagnostic::ConfigurationDiagnostic,
+    configuration_registry::{ConfigurationNode, ConfigurationSource},
+    jsonc,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestFormat {
+    /// JSON, comments and trailing commas are allowed as in settings files.
+    Json,
+    Toml,
+}
+
+impl ManifestFormat {
+    /// Returns the format of the manifest file by its extension, or `None` if it is not a manifest.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?;
+
+        if extension.eq_ignore_ascii_case("json") {
+            Some(Self::Json)
+        } else if extension.eq_ignore_ascii_case("toml") {
+            Some(Self::Toml)
+        } else {
+            None
+        }
+    }
+}
+
+/// Configuration nodes read from declarative contribution manifests, so plugins and teams
+/// can contribute settings without recompiling the workbench. A manifest describes a single
+/// `ConfigurationNode`, its properties map the keys of the settings to their schemas:
+///
+/// ```json
+/// {
+///     "id": "terminal",
+///     "title": "Terminal",
+///     "properties": {
+///         "terminal.fontSize": { "type": "number", "default": 12 }
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct ConfigurationContributions {
+    pub nodes: Vec<ConfigurationNode>,
+    /// Manifests that could not be read, the other manifests are loaded anyway.
+    pub diagnostics: Vec<ConfigurationDiagnostic>,
+}
+
+impl ConfigurationContributions {
+    /// Loads every manifest of the directory, in the order of their file names.
+    /// A missing directory means that nothing is contributed.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", dir.display()))
+            }
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && ManifestFormat::from_path(path).is_some())
+            .collect();
+        paths.sort();
+
+        let mut contributions = Self::default();
+        for path in paths {
+            match load_manifest(&path) {
+                Ok(node) => contributions.nodes.push(node),
+                Err(err) => contributions
+                    .diagnostics
+                    .push(ConfigurationDiagnostic::error(format!("{:#}", err)).with_source(&path)),
+            }
+        }
+
+        Ok(contributions)
+    }
+}
+
+/// Reads the manifest file, the source of the node defaults to the file name
+/// so the settings it contributes can be told apart from the built-in ones.
+pub fn load_manifest(path: &Path) -> Result<ConfigurationNode> {
+    let format = ManifestFormat::from_path(path)
+        .ok_or_else(|| anyhow!("{} is not a JSON or TOML manifest", path.display()))?;
+    let content =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+
+    let mut node = parse_manifest(&content, format)?;
+    if node.source.is_none() {
+        node.source = Some(ConfigurationSource {
+            id: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            display_name: None,
+        });
+    }
+
+    Ok(node)
+}
+
+pub fn parse_manifest(content: &str, format: ManifestFormat) -> Result<ConfigurationNode> {
+    match format {
+        ManifestFormat::Json => {
+            let jsonc::ParseResult { root, errors } = jsonc::parse_tree(content);
+            if let Some(error) = errors.into_iter().next() {
+                return Err(error.into());
+            }
+
+            let value = root.map(|root| root.to_value()).unwrap_or_default();
+            serde_json::from_value(value).context("invalid configuration manifest")
+        }
+        ManifestFormat::Toml => toml::from_str(content).context("invalid configuration manifest"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        configuration_key::ConfigurationKey,
+        configuration_platform::ConfigurationPlatform,
+        configuration_registry::{
+            test_utils::node, ConfigurationNodeType, ConfigurationPropertySchema,
+            ConfigurationRegistry, ConfigurationScope, PropertyMap,
+        },
+        property_key,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_json_manifest() {
+        let node = parse_manifest(
+            r#"{
+    // Settings of the terminal plugin
+    "id": "terminal",
+    "title": "Terminal",
+    "scope": "machine",
+    "properties": {
+        "terminal.fontSize": {
+            "type": "number",
+            "default": 12,
+            "platformDefaults": { "macos": 13 },
+        },
+        "terminal.shell": {
+            "type": "string",
+            "scope": "resource",
+            "stringPattern": "^[a-z]+$",
+            "enum": ["bash", "zsh"],
+        },
+        "[rust].terminal.fontSize": { "type": "number", "default": 14 },
+    },
+}"#,
+            ManifestFormat::Json,
+        )
+        .unwrap();
+
+        assert_eq!(node.id, "terminal");
+
+        let mut registry = ConfigurationRegistry::new();
+        assert!(registry.register_contribution(&node).is_empty());
+
+        let font_size = &registry.get_property("terminal.fontSize").unwrap().schema;
+        assert_eq!(font_size.typ, Some(ConfigurationNodeType::Number));
+        assert_eq!(font_size.scope, Some(ConfigurationScope::Machine));
+        assert_eq!(
+            font_size.default_for(ConfigurationPlatform::MacOS),
+            Some(&json!(13))
+        );
+        assert_eq!(
+            font_size.default_for(ConfigurationPlatform::Linux),
+            Some(&json!(12))
+        );
+
+        // The scope declared on the property wins over the scope of the node.
+        let shell = &registry.get_property("terminal.shell").unwrap().schema;
+        assert_eq!(shell.scope, Some(ConfigurationScope::Resource));
+        assert!(shell.string_pattern.as_ref().unwrap().is_match("zsh"));
+        assert_eq!(shell.enum_items, Some(json!(["bash", "zsh"])));
+        assert_eq!(shell.string_max_length, Some(255));
+
+        assert!(registry.override_identifiers().contains("rust"));
+        assert!(registry
+            .properties()
+            .contains_key(&ConfigurationKey::with_override(
+                "rust",
+                "terminal.fontSize"
+            )));
+    }
+
+    #[test]
+    fn test_parse_toml_manifest() {
+        let node = parse_manifest(
+            r#"
+id = "terminal"
+
+[properties."terminal.cursorBlinking"]
+type = "boolean"
+default = true
+"#,
+            ManifestFormat::Toml,
+        )
+        .unwrap();
+
+        let properties = node.properties.unwrap();
+        let (key, schema) = properties.into_iter().next().unwrap();
+        assert_eq!(key, ConfigurationKey::new("terminal.cursorBlinking"));
+        assert_eq!(schema.typ, Some(ConfigurationNodeType::Bool));
+        assert_eq!(schema.default, Some(json!(true)));
+    }
+
+    #[test]
+    fn test_register_contribution() {
+        let mut properties = PropertyMap::new();
+        properties.insert(
+            property_key!(editor.fontSize),
+            ConfigurationPropertySchema {
+                default: Some(json!(12)),
+                ..Default::default()
+            },
+        );
+        properties.insert(
+            property_key!(security.workspaceTrust),
+            ConfigurationPropertySchema {
+                default: Some(json!(true)),
+                protected_from_contribution: true,
+                ..Default::default()
+            },
+        );
+
+        let mut registry = ConfigurationRegistry::new();
+        registry.register_configuration(&node("editor", properties));
+
+        let contribution = parse_manifest(
+            r#"{
+    "id": "terminal",
+    "properties": {
+        "terminal.fontSize": { "type": "number", "default": 14 },
+        "editor.fontSize": { "type": "number", "default": 20 },
+        "security.workspaceTrust": { "type": "boolean", "default": false },
+    },
+}"#,
+            ManifestFormat::Json,
+        )
+        .unwrap();
+
+        let mut diagnostics = registry.register_contribution(&contribution);
+        diagnostics.sort_by(|a, b| a.key.cmp(&b.key));
+
+        assert!(registry.get_property("terminal.fontSize").is_some());
+        assert_eq!(
+            registry
+                .get_property("editor.fontSize")
+                .unwrap()
+                .schema
+                .default,
+            Some(json!(12))
+        );
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.message.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "Setting `editor.fontSize` is already registered and can't be contributed by `terminal`",
+                "Setting `security.workspaceTrust` is protected from contribution and can't be registered by `terminal`",
+            ]
+        );
+
+        // Protected settings are kept even if the node is registered as a built-in one.
+        registry.register_configuration(&contribution);
+        assert_eq!(
+            registry
+                .get_property("editor.fontSize")
+                .unwrap()
+                .schema
+                .default,
+            Some(json!(20))
+        );
+        assert_eq!(
+            registry
+                .get_property("security.workspaceTrust")
+                .unwrap()
+                .schema
+                .default,
+            Some(json!(true))
+        );
+    }
+
+    #[test]
+    fn test_load_manifests() {
+        let dir = std::env::temp_dir().join(format!(
+            "moss_configuration_contributions_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("terminal.json"),
+            r#"{ "id": "terminal", "properties": { "terminal.fontSize": { "type": "number" } } }"#,
+        )
+        .unwrap();
+        fs::write(dir.join("broken.toml"), "id = ").unwrap();
+        fs::write(dir.join("README.md"), "Not a manifest").unwrap();
+
+        let contributions = ConfigurationContributions::load(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(contributions.nodes.len(), 1);
+        assert_eq!(
+            contributions.nodes[0].source.as_ref().unwrap().id,
+            "terminal"
+        );
+        assert_eq!(contributions.diagnostics.len(), 1);
+        assert_eq!(
+            contributions.diagnostics[0].source,
+            Some(dir.join("broken.toml"))
+        );
+
+        let contributions = ConfigurationContributions::load(&dir).unwrap();
+        assert!(contributions.nodes.is_empty());
+    }
+}
@@ -4,13 +4,15 @@ use radix_trie::Trie;
 use std::sync::Arc;
 
 use super::{
-    configuration_model::{AttributeName, ConfigurationModel},
+    configuration_model::ConfigurationModel, configuration_platform::ConfigurationPlatform,
     configuration_registry::ConfigurationRegistry,
 };
 
 pub struct DefaultConfiguration {
     configuration_model: ArcSwapOption<ConfigurationModel>,
     configuration_registry: Atom<ConfigurationRegistry>,
+    /// The platform the default values are resolved for, see `ConfigurationPropertySchema::default_for`.
+    platform: ConfigurationPlatform,
 }
 
 impl DefaultConfiguration {
@@ -18,9 +20,21 @@ impl DefaultConfiguration {
         Self {
             configuration_model: ArcSwapOption::from(None),
             configuration_registry: registry,
+            platform: ConfigurationPlatform::current(),
         }
     }
 
+    /// Resolves the default values for another platform than the one the application was built for,
+    /// e.g. the one reported by `NativePlatformInfo`.
+    pub fn with_platform(mut self, platform: ConfigurationPlatform) -> Self {
+        self.platform = platform;
+        self
+    }
+
+    pub fn platform(&self) -> ConfigurationPlatform {
+        self.platform
+    }
+
     pub fn initialize(&self, ctx: &mut Context) {
         self.reset_configuration_model(ctx);
     }
@@ -32,22 +46,95 @@ impl DefaultConfiguration {
     fn reset_configuration_model(&self, ctx: &mut Context) {
         let configuration_registry = self.configuration_registry.read(ctx);
         let properties = configuration_registry.properties();
-        let mut new_model = ConfigurationModel {
-            content: Trie::new(),
-            names: Vec::new(),
-            overrides: configuration_registry
+        let mut new_model = ConfigurationModel::new(
+            Trie::new(),
+            Vec::new(),
+            configuration_registry
                 .override_identifiers()
                 .iter()
                 .cloned()
                 .collect(),
-        };
+        );
 
         for (key, property) in properties {
-            if let Some(default_value) = &property.schema.default {
-                new_model.set_value(AttributeName::format(key), default_value.clone());
+            if let Some(default_value) = property.schema.default_for(self.platform) {
+                new_model.set_value(key.clone(), default_value.clone());
+            }
+
+            if let Some(scope) = &property.schema.scope {
+                new_model.set_scope(key.clone(), scope.clone());
             }
         }
 
         self.configuration_model.store(Some(Arc::new(new_model)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attribute_name,
+        configuration_registry::{
+            test_utils::registry_with, ConfigurationPropertySchema, PropertyMap,
+        },
+        property_key,
+    };
+    use platform_core::context_v2::ContextCell;
+    use platform_core::platform::cross::client::CrossPlatformClient;
+    use serde_json::json;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_platform_defaults() {
+        let ctx_cell = ContextCell::new(Rc::new(CrossPlatformClient::new()));
+        let ctx: &mut Context = &mut ctx_cell.borrow_mut();
+
+        let mut properties = PropertyMap::new();
+        properties.insert(
+            property_key!(editor.fontFamily),
+            ConfigurationPropertySchema {
+                default: Some(json!("Droid Sans Mono")),
+                platform_defaults: Some(
+                    [
+                        (ConfigurationPlatform::MacOS, json!("Menlo")),
+                        (ConfigurationPlatform::Windows, json!("Consolas")),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..Default::default()
+            },
+        );
+        properties.insert(
+            property_key!(editor.fontSize),
+            ConfigurationPropertySchema {
+                default: Some(json!(14)),
+                ..Default::default()
+            },
+        );
+        let registry = registry_with(ctx, "editor", properties);
+
+        for (platform, font_family) in [
+            (ConfigurationPlatform::Linux, "Droid Sans Mono"),
+            (ConfigurationPlatform::MacOS, "Menlo"),
+            (ConfigurationPlatform::Windows, "Consolas"),
+        ] {
+            let default_configuration =
+                DefaultConfiguration::new(registry.clone()).with_platform(platform);
+            default_configuration.initialize(ctx);
+            let model = default_configuration.get_configuration_model().unwrap();
+
+            assert_eq!(
+                model.get_value(&attribute_name!(editor.fontFamily)),
+                Some(&json!(font_family)),
+                "default of `editor.fontFamily` on {}",
+                platform
+            );
+            assert_eq!(
+                model.get_value(&attribute_name!(editor.fontSize)),
+                Some(&json!(14))
+            );
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+
+use crate::jsonc::{self, Node, ParseError};
+
+/// Enumeration representing how serious a configuration diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    /// The content could not be read, e.g. a syntax error.
+    Error,
+    /// The content was read but ignored, e.g. an unknown setting.
+    Warning,
+    Info,
+}
+
+/// Location of a diagnostic in the source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticSpan {
+    /// Byte offset in the source text.
+    pub offset: usize,
+    /// Byte length in the source text.
+    pub length: usize,
+    /// 1-based line.
+    pub line: usize,
+    /// 1-based column.
+    pub column: usize,
+}
+
+impl DiagnosticSpan {
+    pub fn from_node(content: &str, node: &Node) -> Self {
+        let (line, column) = jsonc::line_column(content, node.offset);
+
+        Self {
+            offset: node.offset,
+            length: node.length,
+            line,
+            column,
+        }
+    }
+}
+
+/// A problem found while reading a configuration source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigurationDiagnostic {
+    pub severity: DiagnosticSeverity,
+    /// The setting the diagnostic relates to, if any.
+    pub key: Option<String>,
+    /// The file the diagnostic was found in, if the content was read from a file.
+    pub source: Option<PathBuf>,
+    pub span: Option<DiagnosticSpan>,
+    pub message: String,
+}
+
+impl ConfigurationDiagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(DiagnosticSeverity::Error, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(DiagnosticSeverity::Warning, message)
+    }
+
+    pub fn info(message: impl Into<String>) -> Self {
+        Self::new(DiagnosticSeverity::Info, message)
+    }
+
+    fn new(severity: DiagnosticSeverity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            key: None,
+            source: None,
+            span: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    pub fn with_span(mut self, span: DiagnosticSpan) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_source(mut self, source: impl AsRef<Path>) -> Self {
+        self.source = Some(source.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == DiagnosticSeverity::Error
+    }
+}
+
+impl From<ParseError> for ConfigurationDiagnostic {
+    fn from(error: ParseError) -> Self {
+        Self::error(error.message).with_span(DiagnosticSpan {
+            offset: error.offset,
+            length: error.length,
+            line: error.line,
+            column: error.column,
+        })
+    }
+}
+
+impl std::fmt::Display for ConfigurationDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(source) = &self.source {
+            write!(f, "{}", source.display())?;
+            if let Some(span) = &self.span {
+                write!(f, ":{}:{}", span.line, span.column)?;
+            }
+            write!(f, ": ")?;
+        } else if let Some(span) = &self.span {
+            write!(f, "{}:{}: ", span.line, span.column)?;
+        }
+
+        let severity = match self.severity {
+            DiagnosticSeverity::Error => "error",
+            DiagnosticSeverity::Warning => "warning",
+            DiagnosticSeverity::Info => "info",
+        };
+
+        write!(f, "{}: {}", severity, self.message)
+    }
+}
@@ -0,0 +1,262 @@
+use anyhow::{Context as AnyhowContext, Result};
+use futures::channel::oneshot;
+use lazy_regex::Lazy;
+use platform_utl::queue::{thread_backend::ThreadBackend, Processor, Queue};
+use serde_json::Value;
+use std::{fs, io, path::PathBuf};
+
+use crate::{
+    configuration_key::ConfigurationKey,
+    jsonc::{self, FormattingOptions, Node, NodeKind},
+};
+
+/// Service that writes single configuration values back into a settings file.
+/// Writes are serialized through a queue, so concurrent updates never interleave,
+/// and are applied as minimal text edits, so the user's comments and formatting are kept.
+#[derive(Debug)]
+pub struct ConfigurationEditingService {
+    edited_resource: PathBuf,
+    queue: Queue<ThreadBackend, ConfigurationWriteJob>,
+}
+
+#[derive(Debug)]
+struct ConfigurationWriteJob {
+    attribute_name: ConfigurationKey,
+    value: Option<Value>,
+    resource: PathBuf,
+    result_tx: oneshot::Sender<Result<()>>,
+}
+
+#[derive(Debug)]
+struct ConfigurationWriteJobProcessor;
+
+#[async_trait]
+impl Processor<ConfigurationWriteJob> for ConfigurationWriteJobProcessor {
+    async fn process(&self, job: ConfigurationWriteJob) {
+        let result = Self::write(&job.resource, &job.attribute_name, job.value.as_ref());
+
+        // The receiver is gone only if the caller is no longer interested in the result.
+        let _ = job.result_tx.send(result);
+    }
+}
+
+impl ConfigurationWriteJobProcessor {
+    fn write(
+        resource: &PathBuf,
+        attribute_name: &ConfigurationKey,
+        value: Option<&Value>,
+    ) -> Result<()> {
+        let content = match fs::read_to_string(resource) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", resource.display()))
+            }
+        };
+
+        let path = Self::resolve_path(&content, attribute_name, value.is_none());
+        let edits = jsonc::modify(&content, &path, value, &FormattingOptions::detect(&content))
+            .with_context(|| format!("failed to edit {}", resource.display()))?;
+        if edits.is_empty() {
+            return Ok(());
+        }
+
+        fs::write(resource, jsonc::apply_edits(&content, &edits))
+            .with_context(|| format!("failed to write {}", resource.display()))
+    }
+
+    /// Returns the JSON path the attribute is written at, keeping the form the file already uses:
+    /// a setting of a section written as a nested object, e.g. `{ "window": { "defaultWidth": 1200 } }`,
+    /// is written into that object, any other setting by its dotted name, e.g. `"window.defaultWidth"`.
+    /// When the attribute is removed and it is the last setting of a nested object, the object is removed.
+    fn resolve_path(
+        content: &str,
+        attribute_name: &ConfigurationKey,
+        removing: bool,
+    ) -> Vec<String> {
+        let mut path = attribute_name.json_path();
+        let name = &attribute_name.name;
+        if name.is_empty() {
+            return path;
+        }
+        path.pop();
+
+        let root = jsonc::parse_tree(content).root;
+        let mut parent = root.as_ref();
+        if let Some(override_key) = path.first() {
+            parent = parent
+                .and_then(|root| root.find_property(override_key))
+                .and_then(Node::property_value);
+        }
+
+        let segments: Vec<&str> = name.split('.').collect();
+        let mut sections = Vec::new();
+        let mut start = 0;
+        while let Some(object) = parent.filter(|node| node.kind == NodeKind::Object) {
+            if object.find_property(&segments[start..].join(".")).is_some() {
+                break;
+            }
+
+            let section = (start + 1..segments.len()).find_map(|end| {
+                let key = segments[start..end].join(".");
+                object
+                    .find_property(&key)
+                    .and_then(Node::property_value)
+                    .filter(|value| value.kind == NodeKind::Object)
+                    .map(|value| (key, end, value))
+            });
+            let Some((key, end, value)) = section else {
+                break;
+            };
+
+            path.push(key);
+            sections.push(value);
+            parent = Some(value);
+            start = end;
+        }
+        let key = segments[start..].join(".");
+        let is_set = parent.is_some_and(|object| object.find_property(&key).is_some());
+        path.push(key);
+
+        if removing && is_set {
+            while sections
+                .last()
+                .is_some_and(|section| section.children.len() == 1)
+            {
+                sections.pop();
+                path.pop();
+            }
+        }
+
+        path
+    }
+}
+
+impl ConfigurationEditingService {
+    pub fn new(edited_resource: PathBuf) -> Self {
+        Self {
+            edited_resource,
+            queue: Queue::new(
+                Lazy::new(ThreadBackend::new),
+                ConfigurationWriteJobProcessor {},
+            ),
+        }
+    }
+
+    pub fn edited_resource(&self) -> &PathBuf {
+        &self.edited_resource
+    }
+
+    /// Writes the value of the attribute into the edited resource.
+    /// Passing `None` removes the attribute from the resource.
+    pub async fn write(
+        &self,
+        attribute_name: &ConfigurationKey,
+        value: Option<Value>,
+    ) -> Result<()> {
+        let (result_tx, result_rx) = oneshot::channel();
+
+        self.queue
+            .enqueue(ConfigurationWriteJob {
+                attribute_name: attribute_name.clone(),
+                value,
+                resource: self.edited_resource.clone(),
+                result_tx,
+            })
+            .await;
+
+        result_rx
+            .await
+            .context("configuration write job was cancelled")?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attribute_name;
+    use serde_json::json;
+
+    #[test]
+    fn test_write_preserves_comments() {
+        let resource = std::env::temp_dir().join(format!(
+            "moss_configuration_editing_{}.json",
+            std::process::id()
+        ));
+        fs::write(
+            &resource,
+            "{\n    // Font size\n    \"editor.fontSize\": 12\n}",
+        )
+        .unwrap();
+
+        let editing_service = ConfigurationEditingService::new(resource.clone());
+        futures::executor::block_on(async {
+            editing_service
+                .write(&attribute_name!(editor.fontSize), Some(json!(14)))
+                .await
+                .unwrap();
+            editing_service
+                .write(&attribute_name!([rust].editor.fontSize), Some(json!(16)))
+                .await
+                .unwrap();
+        });
+
+        let content = fs::read_to_string(&resource).unwrap();
+        fs::remove_file(&resource).unwrap();
+
+        assert_eq!(
+            content,
+            "{\n    // Font size\n    \"editor.fontSize\": 14,\n    \"[rust]\": {\n        \"editor.fontSize\": 16\n    }\n}"
+        );
+    }
+
+    #[test]
+    fn test_write_keeps_nested_sections() {
+        let resource = std::env::temp_dir().join(format!(
+            "moss_configuration_editing_nested_{}.json",
+            std::process::id()
+        ));
+        fs::write(
+            &resource,
+            "{\n    \"window\": {\n        \"defaultWidth\": 1200\n    },\n    \"editor.fontSize\": 12\n}",
+        )
+        .unwrap();
+
+        let editing_service = ConfigurationEditingService::new(resource.clone());
+        futures::executor::block_on(async {
+            editing_service
+                .write(&attribute_name!(window.defaultHeight), Some(json!(800)))
+                .await
+                .unwrap();
+            editing_service
+                .write(&attribute_name!(editor.tabSize), Some(json!(4)))
+                .await
+                .unwrap();
+        });
+
+        let content = fs::read_to_string(&resource).unwrap();
+        assert_eq!(
+            content,
+            "{\n    \"window\": {\n        \"defaultWidth\": 1200,\n        \"defaultHeight\": 800\n    },\n    \"editor.fontSize\": 12,\n    \"editor.tabSize\": 4\n}"
+        );
+
+        futures::executor::block_on(async {
+            editing_service
+                .write(&attribute_name!(window.defaultWidth), None)
+                .await
+                .unwrap();
+            editing_service
+                .write(&attribute_name!(window.defaultHeight), None)
+                .await
+                .unwrap();
+        });
+
+        let content = fs::read_to_string(&resource).unwrap();
+        fs::remove_file(&resource).unwrap();
+
+        assert_eq!(
+            content,
+            "{\n    \"editor.fontSize\": 12,\n    \"editor.tabSize\": 4\n}"
+        );
+    }
+}
@@ -0,0 +1,32 @@
+/// Errors returned when a configuration value cannot be read or written.
+/// The configuration services return `anyhow::Result`, so callers that need to react
+/// to a specific error can use `anyhow::Error::downcast_ref::<ConfigurationError>()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigurationError {
+    /// The setting is locked by a policy, so its value cannot be changed by the user.
+    PolicyProtected { key: String, policy: String },
+    /// The setting has no value, neither set by the user nor a registered default one.
+    MissingValue { key: String },
+    /// The value of the setting doesn't match the type it is read as.
+    InvalidValue { key: String, reason: String },
+}
+
+impl std::fmt::Display for ConfigurationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigurationError::PolicyProtected { key, policy } => write!(
+                f,
+                "setting `{}` is protected by policy `{}` and cannot be overwritten",
+                key, policy
+            ),
+            ConfigurationError::MissingValue { key } => {
+                write!(f, "setting `{}` has no value", key)
+            }
+            ConfigurationError::InvalidValue { key, reason } => {
+                write!(f, "setting `{}` has an invalid value: {}", key, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigurationError {}
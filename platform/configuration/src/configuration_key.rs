@@ -0,0 +1,270 @@
+use radix_trie::TrieKey;
+
+/// Marks the encoded keys of values set outside of any override.
+const BASE_KEY_MARKER: u8 = 0x01;
+/// Marks the encoded keys of override values, the marker is followed by the override identifier.
+const OVERRIDE_KEY_MARKER: u8 = 0x02;
+/// Terminates the override identifier and every segment of the name in the encoded key.
+const SEGMENT_TERMINATOR: u8 = 0x00;
+
+/// Key of a setting value, e.g. `editor.fontSize`, or `[rust].editor.fontSize` for the value of an override.
+/// The same key is used by the registered properties, the configuration models and their differences.
+///
+/// In a `Trie` the keys are stored by segments, so all the values of a section, e.g. `editor`,
+/// are stored under the key of the section and never mixed with the values of a section
+/// that only shares a prefix, e.g. `editorial`. Override values are stored apart from the others.
+/// Empty segments are dropped from the name, so `editor..fontSize` is the key of `editor.fontSize`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ConfigurationKey {
+    pub override_ident: Option<String>,
+    /// Dotted name of the setting, empty for the root of the configuration.
+    pub name: String,
+}
+
+impl ConfigurationKey {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            override_ident: None,
+            name: normalize_name(name.into()),
+        }
+    }
+
+    pub fn with_override(override_ident: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            override_ident: Some(override_ident.into()),
+            name: normalize_name(name.into()),
+        }
+    }
+
+    /// Parses a key written as in a settings file, e.g. `editor.fontSize` or `[rust].editor.fontSize`.
+    /// A key with several override identifiers, e.g. `[typescript][javascript].editor.tabSize`,
+    /// is parsed into a key for every identifier, in the order they are written.
+    pub fn parse(key: &str) -> Result<Vec<Self>, String> {
+        let mut override_idents: Vec<&str> = Vec::new();
+        let mut rest = key;
+
+        while let Some(tail) = rest.strip_prefix('[') {
+            let (ident, tail) = tail
+                .split_once(']')
+                .ok_or_else(|| "Mismatched brackets in override section".to_string())?;
+            if ident.is_empty() || ident.contains('[') {
+                return Err("Invalid override identifier".to_string());
+            }
+
+            if !override_idents.contains(&ident) {
+                override_idents.push(ident);
+            }
+            rest = tail;
+        }
+
+        let name = rest.strip_prefix('.').unwrap_or(rest);
+        if name.contains(['[', ']']) {
+            return Err("Mismatched brackets in override section".to_string());
+        }
+        if name.split('.').all(str::is_empty) {
+            return Err("Missing identifier".to_string());
+        }
+
+        if override_idents.is_empty() {
+            return Ok(vec![Self::new(name)]);
+        }
+
+        Ok(override_idents
+            .into_iter()
+            .map(|ident| Self::with_override(ident, name))
+            .collect())
+    }
+
+    /// Returns the key of the value that applies outside of the override.
+    pub fn base(&self) -> Self {
+        Self::new(self.name.clone())
+    }
+
+    pub fn is_override(&self) -> bool {
+        self.override_ident.is_some()
+    }
+
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        self.name.split('.').filter(|segment| !segment.is_empty())
+    }
+
+    /// Returns the name relative to the section, e.g. `fontSize` for `editor.fontSize` in `editor`,
+    /// or an empty name for the section itself. The override identifier is not compared.
+    pub fn relative_name(&self, section: &str) -> Option<&str> {
+        if section.is_empty() {
+            return Some(&self.name);
+        }
+
+        match self.name.strip_prefix(section)? {
+            "" => Some(""),
+            rest => rest.strip_prefix('.'),
+        }
+    }
+
+    /// Checks whether the key is the section, e.g. `editor`, or a setting in it, e.g. `editor.fontSize`.
+    pub fn is_in_section(&self, section: &str) -> bool {
+        self.relative_name(section).is_some()
+    }
+
+    /// Returns the path of object keys under which the value is stored in a settings file,
+    /// e.g. `["[rust]", "editor.fontSize"]` for `[rust].editor.fontSize`.
+    pub fn json_path(&self) -> Vec<String> {
+        let mut path = Vec::new();
+
+        if let Some(ident) = &self.override_ident {
+            path.push(format!("[{}]", ident));
+        }
+
+        if !self.name.is_empty() {
+            path.push(self.name.clone());
+        }
+
+        path
+    }
+}
+
+/// Drops the empty segments of the name. Keys are compared by their names,
+/// so two names with the same segments must be equal to be stored under the same key in a `Trie`.
+fn normalize_name(name: String) -> String {
+    if name.is_empty() || !name.split('.').any(str::is_empty) {
+        return name;
+    }
+
+    name.split('.')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+impl std::fmt::Display for ConfigurationKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.override_ident, self.name.is_empty()) {
+            (Some(ident), true) => write!(f, "[{}]", ident),
+            (Some(ident), false) => write!(f, "[{}].{}", ident, self.name),
+            (None, _) => write!(f, "{}", self.name),
+        }
+    }
+}
+
+impl TrieKey for ConfigurationKey {
+    fn encode_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.name.len() + 2);
+
+        match &self.override_ident {
+            Some(ident) => {
+                bytes.push(OVERRIDE_KEY_MARKER);
+                bytes.extend_from_slice(ident.as_bytes());
+                bytes.push(SEGMENT_TERMINATOR);
+            }
+            None => bytes.push(BASE_KEY_MARKER),
+        }
+
+        for segment in self.segments() {
+            bytes.extend_from_slice(segment.as_bytes());
+            bytes.push(SEGMENT_TERMINATOR);
+        }
+
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigurationKey;
+    use radix_trie::{Trie, TrieCommon};
+
+    #[test]
+    fn test_parse_and_display() {
+        let keys = ConfigurationKey::parse("[rust].editor.tabSize").unwrap();
+        assert_eq!(
+            keys,
+            vec![ConfigurationKey::with_override("rust", "editor.tabSize")]
+        );
+        assert_eq!(keys[0].to_string(), "[rust].editor.tabSize");
+        assert_eq!(keys[0].base(), ConfigurationKey::new("editor.tabSize"));
+
+        let keys = ConfigurationKey::parse("editor.tabSize").unwrap();
+        assert_eq!(keys, vec![ConfigurationKey::new("editor.tabSize")]);
+        assert_eq!(keys[0].to_string(), "editor.tabSize");
+    }
+
+    #[test]
+    fn test_parse_with_multiple_overrides() {
+        assert_eq!(
+            ConfigurationKey::parse("[typescript][javascript].editor.tabSize").unwrap(),
+            vec![
+                ConfigurationKey::with_override("typescript", "editor.tabSize"),
+                ConfigurationKey::with_override("javascript", "editor.tabSize"),
+            ]
+        );
+        assert_eq!(
+            ConfigurationKey::parse("[typescript][javascript].editor").unwrap(),
+            vec![
+                ConfigurationKey::with_override("typescript", "editor"),
+                ConfigurationKey::with_override("javascript", "editor"),
+            ]
+        );
+        // An identifier written twice gives a single key.
+        assert_eq!(
+            ConfigurationKey::parse("[rust][rust].editor.tabSize").unwrap(),
+            vec![ConfigurationKey::with_override("rust", "editor.tabSize")]
+        );
+    }
+
+    #[test]
+    fn test_parse_with_invalid_format() {
+        assert!(ConfigurationKey::parse("[rust.editor.fontSize").is_err());
+        assert!(ConfigurationKey::parse("rust].editor.fontSize").is_err());
+        assert!(ConfigurationKey::parse("[rust]editor[x].fontSize").is_err());
+        assert!(ConfigurationKey::parse("[][rust].editor.fontSize").is_err());
+        assert!(ConfigurationKey::parse("[typescript][javascript]").is_err());
+        assert!(ConfigurationKey::parse("").is_err());
+    }
+
+    #[test]
+    fn test_trie_section_lookup() {
+        let mut trie = Trie::new();
+        trie.insert(ConfigurationKey::new("editor.fontSize"), 12);
+        trie.insert(ConfigurationKey::new("editor.minimap.enabled"), 1);
+        trie.insert(ConfigurationKey::new("editorial.mode"), 2);
+        trie.insert(ConfigurationKey::with_override("rust", "editor.tabSize"), 3);
+
+        let section = ConfigurationKey::new("editor");
+        let mut names: Vec<_> = trie
+            .get_raw_descendant(&section)
+            .map(|subtrie| {
+                subtrie
+                    .keys()
+                    .filter(|key| !key.is_override() && key.is_in_section("editor"))
+                    .map(|key| key.name.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort();
+
+        assert_eq!(names, vec!["editor.fontSize", "editor.minimap.enabled"]);
+        assert!(!ConfigurationKey::new("editorial.mode").is_in_section("editor"));
+    }
+
+    #[test]
+    fn test_keys_with_empty_segments() {
+        assert_eq!(
+            ConfigurationKey::new("editor.fontSize."),
+            ConfigurationKey::new("editor.fontSize")
+        );
+        assert_eq!(
+            ConfigurationKey::parse("[rust].editor..fontSize").unwrap(),
+            vec![ConfigurationKey::with_override("rust", "editor.fontSize")]
+        );
+
+        let mut trie = Trie::new();
+        trie.insert(ConfigurationKey::new("editor.fontSize."), 12);
+        trie.insert(ConfigurationKey::new("editor..fontSize"), 14);
+
+        assert_eq!(trie.len(), 1);
+        assert_eq!(
+            trie.get(&ConfigurationKey::new("editor.fontSize")),
+            Some(&14)
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use serde_json::Value;
+use std::sync::Arc;
+
+pub type MigrationTransform = Arc<dyn Fn(Value) -> Option<Value> + Send + Sync>;
+
+/// Describes how the value of a deprecated setting is moved to the setting that replaces it.
+/// Migrations are registered with `ConfigurationRegistry::register_migration` and applied
+/// by `ConfigurationParser` when a settings file is read.
+#[derive(Clone)]
+pub struct ConfigurationMigration {
+    pub key: String,
+    pub new_key: String,
+    transform: Option<MigrationTransform>,
+}
+
+impl std::fmt::Debug for ConfigurationMigration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigurationMigration")
+            .field("key", &self.key)
+            .field("new_key", &self.new_key)
+            .field("transform", &self.transform.is_some())
+            .finish()
+    }
+}
+
+impl ConfigurationMigration {
+    /// The setting was renamed, its value is kept as is.
+    pub fn rename(key: impl Into<String>, new_key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            new_key: new_key.into(),
+            transform: None,
+        }
+    }
+
+    /// The setting was replaced by one with a different value, e.g. a boolean by an enum.
+    /// The transform returns `None` for the values it can't migrate.
+    pub fn transform(
+        key: impl Into<String>,
+        new_key: impl Into<String>,
+        transform: impl Fn(Value) -> Option<Value> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            key: key.into(),
+            new_key: new_key.into(),
+            transform: Some(Arc::new(transform)),
+        }
+    }
+
+    pub fn migrate(&self, value: Value) -> Option<Value> {
+        match &self.transform {
+            Some(transform) => transform(value),
+            None => Some(value),
+        }
+    }
+}
+
+/// A deprecated setting found in a settings file and read as the setting that replaces it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigratedSetting {
+    pub key: String,
+    pub new_key: String,
+    pub override_ident: Option<String>,
+    /// The value after the migration.
+    pub value: Value,
+}
@@ -1,123 +1,74 @@
 use arc_swap::{ArcSwap, ArcSwapOption};
-use hashbrown::HashSet;
+use hashbrown::{HashMap, HashSet};
 use platform_core::base::collection::extend::Extend;
 use radix_trie::{Trie, TrieCommon};
 use serde_json::Value;
-use std::sync::Arc;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-pub struct AttributeName {
-    pub override_ident: Option<String>,
-    pub name: Option<String>,
-}
-
-impl AttributeName {
-    pub(super) fn format(name: impl std::fmt::Display) -> String {
-        format!("$.{}", name)
-    }
-
-    pub(super) fn format_with_override(
-        name: impl std::fmt::Display,
-        override_ident: impl std::fmt::Display,
-    ) -> String {
-        format!("$.[{}].{}", override_ident, name)
-    }
-
-    pub fn must_get_name(&self) -> String {
-        self.name
-            .clone()
-            .unwrap_or(String::from("<undefined_attribute_name>"))
-    }
-
-    pub fn is_with_override(&self) -> bool {
-        self.override_ident.is_some()
-    }
-}
-
-impl ToString for AttributeName {
-    fn to_string(&self) -> String {
-        let mut result = String::from("$.");
-
-        let override_part = self
-            .override_ident
-            .as_ref()
-            .map_or(String::new(), |ident| format!("[{}]", ident));
-
-        let name_part = self.name.as_ref().map_or(String::new(), |name| {
-            if self.override_ident.is_some() {
-                format!(".{}", name)
-            } else {
-                name.clone()
-            }
-        });
-
-        result.push_str(&override_part);
-        result.push_str(&name_part);
-
-        result
-    }
-}
+use crate::{
+    configuration_diagnostic::ConfigurationDiagnostic, configuration_key::ConfigurationKey,
+    configuration_migration::MigratedSetting, configuration_registry::ConfigurationScope,
+};
 
-/// A macro to create an `AttributeName` struct with optional override identifier or name.
+/// Creates the `ConfigurationKey` of an attribute, with an optional override identifier.
 ///
 /// # Examples
 ///
 /// Basic usage with no overrides:
 ///
 /// ```rust
+/// # use platform_configuration::attribute_name;
 /// let attribute_name = attribute_name!(editor.fontSize);
 /// assert_eq!(attribute_name.override_ident, None);
-/// assert_eq!(attribute_name.name, Some("editor.fontSize".to_string()));
+/// assert_eq!(attribute_name.name, "editor.fontSize");
 /// ```
 ///
 /// Usage with an override:
 ///
 /// ```rust
+/// # use platform_configuration::attribute_name;
 /// let attribute_name = attribute_name!([rust].editor.fontSize);
 /// assert_eq!(attribute_name.override_ident, Some("rust".to_string()));
-/// assert_eq!(attribute_name.name, Some("editor.fontSize".to_string()));
+/// assert_eq!(attribute_name.name, "editor.fontSize");
 /// ```
 ///
-/// Usage with an override only:
+/// Usage with an override only, the root of the override:
 ///
 /// ```rust
+/// # use platform_configuration::attribute_name;
 /// let attribute_name = attribute_name!([rust]);
 /// assert_eq!(attribute_name.override_ident, Some("rust".to_string()));
-/// assert_eq!(attribute_name.name, None);
+/// assert_eq!(attribute_name.name, "");
 /// ```
 #[macro_export]
 macro_rules! attribute_name {
     // Handle override with sub-identifiers
-    ([$override:ident] . $ident:ident $(. $subident:ident)*) => {{
-        let override_ident = Some(stringify!($override).to_string());
-        let name = Some(concat!(stringify!($ident), $(concat!(".", stringify!($subident))),*).to_string());
-        $crate::configuration_model::AttributeName {
-            override_ident,
-            name,
-        }
-    }};
+    ([$override:ident] . $ident:ident $(. $subident:ident)*) => {
+        $crate::configuration_key::ConfigurationKey::with_override(
+            stringify!($override),
+            concat!(stringify!($ident), $(concat!(".", stringify!($subident))),*),
+        )
+    };
 
     // Handle override without sub-identifiers
-    ([$override:ident]) => {{
-        let override_ident = Some(stringify!($override).to_string());
-        $crate::configuration_model::AttributeName {
-            override_ident,
-            name: None,
-        }
-    }};
+    ([$override:ident]) => {
+        $crate::configuration_key::ConfigurationKey::with_override(stringify!($override), "")
+    };
 
     // Handle no override with sub-identifiers
-    ($ident:ident $(. $subident:ident)*) => {{
-        let name = Some(concat!(stringify!($ident), $(concat!(".", stringify!($subident))),*).to_string());
-        $crate::configuration_model::AttributeName {
-            override_ident: None,
-            name,
-        }
-    }};
+    ($ident:ident $(. $subident:ident)*) => {
+        $crate::configuration_key::ConfigurationKey::new(
+            concat!(stringify!($ident), $(concat!(".", stringify!($subident))),*),
+        )
+    };
 }
 
 /// Enum representing the various configuration targets in Moss Compass.
 /// These targets specify where the configuration settings should be applied.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ConfigurationTarget {
     /// Platform specific configuration.
     /// These settings apply to the entire application and cannot be overridden in local, workspace, etc.
@@ -131,6 +82,11 @@ pub enum ConfigurationTarget {
     /// These settings apply to the specific workspace and can override by`ConfigurationTarget::User` settings.
     Workspace,
 
+    /// Workspace folder specific configuration.
+    /// These settings apply to the resources in a folder of a multi-root workspace
+    /// and override `ConfigurationTarget::Workspace` settings for them.
+    WorkspaceFolder,
+
     /// Default configuration.
     /// These settings represent the default values provided by Moss Compass and can be overridden by any other configuration target.
     Default,
@@ -138,13 +94,72 @@ pub enum ConfigurationTarget {
     /// Memory specific configuration.
     /// These settings are stored in memory and are not persisted. They can be used for temporary configurations.
     Memory,
+
+    /// Policy configuration.
+    /// These settings are set by an administrator and can't be overridden by any other configuration target.
+    Policy,
+}
+
+impl ConfigurationTarget {
+    /// Scopes of the settings that can be set in this target.
+    pub fn scopes(&self) -> &'static [ConfigurationScope] {
+        match self {
+            ConfigurationTarget::Workspace => {
+                &[ConfigurationScope::Window, ConfigurationScope::Resource]
+            }
+            ConfigurationTarget::WorkspaceFolder => &[ConfigurationScope::Resource],
+            ConfigurationTarget::Platform
+            | ConfigurationTarget::User
+            | ConfigurationTarget::Default
+            | ConfigurationTarget::Memory
+            | ConfigurationTarget::Policy => &[
+                ConfigurationScope::Application,
+                ConfigurationScope::Machine,
+                ConfigurationScope::Window,
+                ConfigurationScope::Resource,
+            ],
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigurationTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ConfigurationTarget::Platform => "platform",
+            ConfigurationTarget::User => "user",
+            ConfigurationTarget::Workspace => "workspace",
+            ConfigurationTarget::WorkspaceFolder => "workspace folder",
+            ConfigurationTarget::Default => "default",
+            ConfigurationTarget::Memory => "memory",
+            ConfigurationTarget::Policy => "policy",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Context in which a value is read, e.g. the language and the file of an editor.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigurationOverrides {
+    /// Override identifier, e.g. `rust` for `[rust]` values.
+    pub override_ident: Option<String>,
+    /// The resource the value applies to, used for settings with `ConfigurationScope::Resource`.
+    pub resource: Option<PathBuf>,
 }
 
 #[derive(Debug)]
 pub struct ConfigurationModel {
-    pub(super) content: Trie<String, Value>,
-    pub(super) names: Vec<String>,
+    pub(super) content: Trie<ConfigurationKey, Value>,
+    pub(super) names: Vec<ConfigurationKey>,
     pub(super) overrides: Vec<String>,
+    pub(super) diagnostics: Vec<ConfigurationDiagnostic>,
+    /// Names of the policies that set the values.
+    /// Only the model created by `ConfigurationPolicy` has them.
+    pub(super) policy_names: HashMap<ConfigurationKey, String>,
+    /// Scopes of the registered settings.
+    pub(super) scopes: HashMap<ConfigurationKey, ConfigurationScope>,
+    /// Deprecated settings read from the source as the settings that replace them.
+    pub(super) migrated: Vec<MigratedSetting>,
 }
 
 impl Clone for ConfigurationModel {
@@ -153,16 +168,28 @@ impl Clone for ConfigurationModel {
             content: self.content.clone(),
             names: self.names.clone(),
             overrides: self.overrides.clone(),
+            diagnostics: self.diagnostics.clone(),
+            policy_names: self.policy_names.clone(),
+            scopes: self.scopes.clone(),
+            migrated: self.migrated.clone(),
         }
     }
 }
 
 impl ConfigurationModel {
-    pub fn new(content: Trie<String, Value>, names: Vec<String>, overrides: Vec<String>) -> Self {
+    pub fn new(
+        content: Trie<ConfigurationKey, Value>,
+        names: Vec<ConfigurationKey>,
+        overrides: Vec<String>,
+    ) -> Self {
         Self {
             content,
             names,
             overrides,
+            diagnostics: Vec::new(),
+            policy_names: HashMap::new(),
+            scopes: HashMap::new(),
+            migrated: Vec::new(),
         }
     }
 
@@ -171,6 +198,10 @@ impl ConfigurationModel {
             content: Trie::new(),
             names: Vec::new(),
             overrides: Vec::new(),
+            diagnostics: Vec::new(),
+            policy_names: HashMap::new(),
+            scopes: HashMap::new(),
+            migrated: Vec::new(),
         }
     }
 
@@ -178,17 +209,108 @@ impl ConfigurationModel {
         self.content.is_empty() && self.names.len() == 0 && self.overrides.len() == 0
     }
 
-    pub fn get_attribute_names(&self) -> &Vec<String> {
+    pub fn get_attribute_names(&self) -> &Vec<ConfigurationKey> {
         &self.names
     }
 
-    pub fn get_value(&self, attribute_name: &AttributeName) -> Option<&Value> {
-        self.content.get(&attribute_name.to_string())
+    /// Returns the keys of all values, including the override values.
+    pub fn keys(&self) -> impl Iterator<Item = &ConfigurationKey> {
+        self.content.keys()
+    }
+
+    /// Problems found while reading the source of the model.
+    /// A model with errors contains the values that could still be read from the source.
+    pub fn diagnostics(&self) -> &Vec<ConfigurationDiagnostic> {
+        &self.diagnostics
+    }
+
+    pub fn migrated_settings(&self) -> &Vec<MigratedSetting> {
+        &self.migrated
+    }
+
+    pub fn set_diagnostics_source(&mut self, source: &Path) {
+        for diagnostic in self.diagnostics.iter_mut() {
+            diagnostic.source = Some(source.to_path_buf());
+        }
+    }
+
+    pub fn get_value(&self, attribute_name: &ConfigurationKey) -> Option<&Value> {
+        self.get(attribute_name)
+    }
+
+    pub fn get(&self, key: &ConfigurationKey) -> Option<&Value> {
+        self.content.get(key)
+    }
+
+    /// Returns all values stored under the section as a nested object,
+    /// e.g. `{"fontSize": 12, "tabSize": 4}` for `editor`.
+    /// If the section itself holds a value and has no children, that value is returned.
+    ///
+    /// Override values are only included when the section has an override identifier,
+    /// in which case only the values of that override are returned, see `Configuration::get_section`
+    /// for the values with the override applied.
+    pub fn get_section(&self, section: &ConfigurationKey) -> Option<Value> {
+        let mut result = serde_json::Map::new();
+        let mut exact_value = None;
+
+        if let Some(subtrie) = self.content.get_raw_descendant(section) {
+            for (key, value) in subtrie.iter() {
+                if key.override_ident != section.override_ident {
+                    continue;
+                }
+
+                match key.relative_name(&section.name) {
+                    Some("") => exact_value = Some(value.clone()),
+                    Some(relative_name) => insert_nested(&mut result, relative_name, value.clone()),
+                    None => {}
+                }
+            }
+        }
+
+        if result.is_empty() {
+            exact_value
+        } else {
+            Some(Value::Object(result))
+        }
+    }
+
+    pub fn get_scope(&self, attribute_name: &ConfigurationKey) -> Option<&ConfigurationScope> {
+        self.scopes.get(attribute_name)
+    }
+
+    pub(super) fn set_scope(&mut self, key: ConfigurationKey, scope: ConfigurationScope) {
+        self.scopes.insert(key, scope);
+    }
+
+    /// Returns the name of the policy that sets the value of the attribute, if any.
+    pub fn get_policy_name(&self, attribute_name: &ConfigurationKey) -> Option<&str> {
+        self.policy_names.get(attribute_name).map(String::as_str)
+    }
+
+    pub(super) fn set_policy_value(
+        &mut self,
+        key: ConfigurationKey,
+        value: serde_json::Value,
+        policy_name: String,
+    ) {
+        self.policy_names.insert(key.clone(), policy_name);
+        self.set_value(key, value);
+    }
+
+    pub fn set_value(&mut self, key: ConfigurationKey, value: serde_json::Value) {
+        if !self.names.contains(&key) {
+            self.names.push(key.clone());
+        }
+
+        self.content.insert(key, value);
     }
 
-    pub fn set_value(&mut self, attribute_name: String, value: serde_json::Value) {
-        self.content.insert(attribute_name.clone(), value);
-        self.names.push(attribute_name);
+    /// Removes the value stored under the key, returning it if it was set.
+    pub fn remove_value(&mut self, key: &ConfigurationKey) -> Option<Value> {
+        self.names.retain(|name| name != key);
+        self.policy_names.remove(key);
+
+        self.content.remove(key)
     }
 
     // TODO:
@@ -203,6 +325,21 @@ impl ConfigurationModel {
         let mut result = self.clone();
 
         for other in others {
+            // A policy locks the setting for every override identifier too,
+            // so the override values of the previous layers can't win over the policy value.
+            // Override values are only stored in the content, they are not listed in the names.
+            if !other.policy_names.is_empty() {
+                let locked_keys: Vec<ConfigurationKey> = result
+                    .content
+                    .keys()
+                    .filter(|key| key.is_override() && other.policy_names.contains_key(&key.base()))
+                    .cloned()
+                    .collect();
+                for key in locked_keys.iter() {
+                    result.remove_value(key);
+                }
+            }
+
             result.content.extend(other.content.iter());
             result.names.extend(
                 other
@@ -210,15 +347,61 @@ impl ConfigurationModel {
                     .iter()
                     .filter(|key| !result.names.contains(key))
                     .cloned()
+                    .collect::<Vec<ConfigurationKey>>(),
+            );
+            result.policy_names.extend(other.policy_names.clone());
+            result.scopes.extend(other.scopes.clone());
+            result.overrides.extend(
+                other
+                    .overrides
+                    .iter()
+                    .filter(|ident| !result.overrides.contains(ident))
+                    .cloned()
                     .collect::<Vec<String>>(),
             );
-            result.overrides.extend(other.overrides.clone());
         }
 
         result
     }
 }
 
+fn insert_nested(object: &mut serde_json::Map<String, Value>, key: &str, value: Value) {
+    match key.split_once('.') {
+        None => {
+            object.insert(key.to_string(), value);
+        }
+        Some((head, rest)) => {
+            let child = object
+                .entry(head.to_string())
+                .or_insert_with(|| Value::Object(serde_json::Map::new()));
+            if !child.is_object() {
+                *child = Value::Object(serde_json::Map::new());
+            }
+
+            if let Value::Object(child) = child {
+                insert_nested(child, rest, value);
+            }
+        }
+    }
+}
+
+/// Recursively merges `other` into `target`, with the values of `other` taking precedence.
+fn merge_values(target: &mut Value, other: Value) {
+    match (target, other) {
+        (Value::Object(target), Value::Object(other)) => {
+            for (key, value) in other {
+                match target.get_mut(&key) {
+                    Some(target_value) => merge_values(target_value, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, other) => *target = other,
+    }
+}
+
 #[derive(Debug)]
 pub struct InspectedConfigurationValue {
     attribute_name: String,
@@ -236,32 +419,172 @@ impl InspectedConfigurationValue {
     pub fn value(&self) -> &Option<serde_json::Value> {
         &self.value
     }
+
+    /// Override identifiers for which the attribute has a value, e.g. `["rust"]` for `editor.fontSize`
+    /// if `[rust].editor.fontSize` is set.
+    pub fn seen_in_overrides(&self) -> &Vec<String> {
+        &self.seen_in_overrides
+    }
     // TODO: Rewrite using override keys and getting sections
-    pub fn get_default_value(&self, attribute_name: &AttributeName) -> Option<&serde_json::Value> {
+    pub fn get_default_value(
+        &self,
+        attribute_name: &ConfigurationKey,
+    ) -> Option<&serde_json::Value> {
         self.default_configuration.get_value(attribute_name)
     }
 
     // TODO: Rewrite using override keys and getting sections
-    pub fn get_policy_value(&self, attribute_name: &AttributeName) -> Option<&serde_json::Value> {
+    pub fn get_policy_value(
+        &self,
+        attribute_name: &ConfigurationKey,
+    ) -> Option<&serde_json::Value> {
         self.policy_configuration.get_value(attribute_name)
     }
+
+    /// Returns the name of the policy that locks the value of the attribute, if any.
+    pub fn get_policy_name(&self, attribute_name: &ConfigurationKey) -> Option<&str> {
+        self.policy_configuration.get_policy_name(attribute_name)
+    }
 }
 
 #[derive(Debug)]
 pub struct Configuration {
     default_configuration: Arc<ConfigurationModel>,
-    policy_configuration: Arc<ConfigurationModel>,
+    policy_configuration: ArcSwap<ConfigurationModel>,
     user_configuration: ArcSwap<ConfigurationModel>,
-    workspace_configuration: Arc<ConfigurationModel>,
-    inmem_configuration: Arc<ConfigurationModel>,
+    workspace_configuration: ArcSwap<ConfigurationModel>,
+    /// Configurations of the folders of a multi-root workspace, keyed by folder path.
+    folder_configurations: ArcSwap<HashMap<PathBuf, Arc<ConfigurationModel>>>,
+    inmem_configuration: ArcSwap<ConfigurationModel>,
     consolidated_configuration: ArcSwapOption<ConfigurationModel>,
+    /// Root folder of the workspace, the workspace values of resource settings only apply to resources in it.
+    workspace_root: Option<PathBuf>,
 }
 
-// TODO: add overrides
+/// Keys that differ between two versions of a configuration model, including the keys of override values.
+#[derive(Debug, Default)]
 pub struct ConfigurationDifference {
-    pub added: Vec<String>,
-    pub modified: Vec<String>,
-    pub removed: Vec<String>,
+    pub added: Vec<ConfigurationKey>,
+    pub modified: Vec<ConfigurationKey>,
+    pub removed: Vec<ConfigurationKey>,
+    /// Identifiers of the overrides that have changed values.
+    pub overrides: Vec<String>,
+}
+
+impl ConfigurationDifference {
+    pub fn extend(&mut self, other: ConfigurationDifference) {
+        self.added.extend(other.added);
+        self.modified.extend(other.modified);
+        self.removed.extend(other.removed);
+        for ident in other.overrides {
+            if !self.overrides.contains(&ident) {
+                self.overrides.push(ident);
+            }
+        }
+    }
+}
+
+/// Event describing which settings of a configuration target have changed.
+#[derive(Debug, Clone)]
+pub struct ConfigurationChangeEvent {
+    /// The configuration layer that has changed.
+    pub source: ConfigurationTarget,
+    /// Changed settings, including the ones that only changed in an override.
+    affected_keys: Vec<String>,
+    /// Settings changed outside of any override.
+    keys: Vec<String>,
+    /// Settings changed in each override, keyed by override identifier.
+    overrides: Vec<(String, Vec<String>)>,
+}
+
+impl ConfigurationChangeEvent {
+    pub fn new(source: ConfigurationTarget, difference: ConfigurationDifference) -> Self {
+        let mut keys = Vec::new();
+        let mut overrides: Vec<(String, Vec<String>)> = Vec::new();
+
+        for key in difference
+            .added
+            .iter()
+            .chain(difference.modified.iter())
+            .chain(difference.removed.iter())
+        {
+            match &key.override_ident {
+                Some(ident) => match overrides.iter_mut().find(|(other, _)| other == ident) {
+                    Some((_, names)) => names.push(key.name.clone()),
+                    None => overrides.push((ident.clone(), vec![key.name.clone()])),
+                },
+                None => keys.push(key.name.clone()),
+            }
+        }
+
+        keys.sort();
+        keys.dedup();
+        overrides.sort();
+        for (_, names) in overrides.iter_mut() {
+            names.sort();
+            names.dedup();
+        }
+
+        let mut affected_keys: Vec<String> = keys
+            .iter()
+            .chain(overrides.iter().flat_map(|(_, names)| names.iter()))
+            .cloned()
+            .collect();
+        affected_keys.sort();
+        affected_keys.dedup();
+
+        Self {
+            source,
+            affected_keys,
+            keys,
+            overrides,
+        }
+    }
+
+    pub fn affected_keys(&self) -> &[String] {
+        &self.affected_keys
+    }
+
+    /// Returns the identifiers of the overrides with changed values, e.g. `rust`.
+    pub fn affected_overrides(&self) -> impl Iterator<Item = &str> {
+        self.overrides.iter().map(|(ident, _)| ident.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.affected_keys.is_empty()
+    }
+
+    /// Checks whether the given section, e.g. `editor` or `editor.fontSize`, is affected by the change,
+    /// in any override or outside of them.
+    pub fn affects_configuration(&self, section: &str) -> bool {
+        self.affected_keys
+            .iter()
+            .any(|key| is_in_section(key, section))
+    }
+
+    /// Checks whether the value of the section read for the override identifier has changed,
+    /// see `Configuration::get_value_for_override`. Without an identifier only the changes
+    /// outside of overrides are considered.
+    pub fn affects(&self, section: &str, override_ident: Option<&str>) -> bool {
+        if self.keys.iter().any(|key| is_in_section(key, section)) {
+            return true;
+        }
+
+        override_ident.is_some_and(|override_ident| {
+            self.overrides
+                .iter()
+                .filter(|(ident, _)| ident == override_ident)
+                .flat_map(|(_, names)| names.iter())
+                .any(|key| is_in_section(key, section))
+        })
+    }
+}
+
+fn is_in_section(key: &str, section: &str) -> bool {
+    key == section
+        || key
+            .strip_prefix(section)
+            .is_some_and(|rest| rest.starts_with('.'))
 }
 
 impl Configuration {
@@ -274,42 +597,161 @@ impl Configuration {
     ) -> Self {
         Configuration {
             default_configuration: default_model,
-            policy_configuration: policy_model,
+            policy_configuration: ArcSwap::new(policy_model),
             user_configuration: ArcSwap::new(user_model),
-            workspace_configuration: workspace_model,
-            inmem_configuration: inmem_model,
+            workspace_configuration: ArcSwap::new(workspace_model),
+            folder_configurations: ArcSwap::new(Arc::new(HashMap::new())),
+            inmem_configuration: ArcSwap::new(inmem_model),
             consolidated_configuration: ArcSwapOption::from(None),
+            workspace_root: None,
         }
     }
 
-    // TODO: implement `section` functionality
+    pub fn with_workspace_root(mut self, workspace_root: Option<PathBuf>) -> Self {
+        self.workspace_root = workspace_root;
+        self
+    }
+
+    /// Returns all values under the section as a nested object, so that whole groups of settings
+    /// can be deserialized at once. If the section has an override identifier, e.g. `[rust].editor`,
+    /// the values of the override are applied on top of the values of the section.
+    pub fn get_section(&self, section: &ConfigurationKey) -> Option<Value> {
+        let consolidated_model = self.get_consolidated_configuration();
+
+        let base_value = consolidated_model.get_section(&section.base());
+
+        if !section.is_override() {
+            return base_value;
+        }
+
+        match (base_value, consolidated_model.get_section(section)) {
+            (Some(mut base_value), Some(override_value)) => {
+                merge_values(&mut base_value, override_value);
+                Some(base_value)
+            }
+            (base_value, override_value) => override_value.or(base_value),
+        }
+    }
+
+    /// Returns the value of the attribute. If the attribute has an override identifier,
+    /// e.g. `[rust].editor.fontSize`, the override value is returned if it is set in any layer,
+    /// otherwise the value of the attribute without the override, e.g. `editor.fontSize`.
+    pub fn get_value(&self, attribute_name: &ConfigurationKey) -> Option<Value> {
+        let consolidated_model = self.get_consolidated_configuration();
+
+        Self::resolve_value(&consolidated_model, attribute_name).cloned()
+    }
+
+    /// Same as `get_value`, for an override identifier that is only known at runtime,
+    /// e.g. the language of the file being edited.
+    pub fn get_value_for_override(
+        &self,
+        attribute_name: &ConfigurationKey,
+        override_ident: Option<&str>,
+    ) -> Option<Value> {
+        self.get_value(&ConfigurationKey {
+            override_ident: override_ident.map(str::to_string),
+            name: attribute_name.name.clone(),
+        })
+    }
+
+    /// Returns the value of the attribute in the given context. Values of resource settings
+    /// set in the workspace only apply if the resource is in the workspace root folder
+    /// or in one of the workspace folders, values set in a workspace folder only apply
+    /// to the resources in that folder.
+    pub fn get_value_with_overrides(
+        &self,
+        attribute_name: &ConfigurationKey,
+        overrides: &ConfigurationOverrides,
+    ) -> Option<Value> {
+        let attribute_name = ConfigurationKey {
+            override_ident: overrides
+                .override_ident
+                .clone()
+                .or_else(|| attribute_name.override_ident.clone()),
+            name: attribute_name.name.clone(),
+        };
+
+        let Some(resource) = &overrides.resource else {
+            return self.get_value(&attribute_name);
+        };
 
-    pub fn get_value(&self, attribute_name: &AttributeName) -> Option<Value> {
         let consolidated_model = self.get_consolidated_configuration();
+        if consolidated_model.get_scope(&attribute_name.base())
+            != Some(&ConfigurationScope::Resource)
+        {
+            return Self::resolve_value(&consolidated_model, &attribute_name).cloned();
+        }
+
+        let folder_model = self.get_folder_configuration(resource);
+        let is_in_workspace = folder_model.is_some()
+            || self
+                .workspace_root
+                .as_ref()
+                .is_none_or(|workspace_root| resource.starts_with(workspace_root));
+
+        if is_in_workspace && folder_model.is_none() {
+            return Self::resolve_value(&consolidated_model, &attribute_name).cloned();
+        }
+
+        let mut layers = vec![self.user_configuration.load_full()];
+        if is_in_workspace {
+            layers.push(self.workspace_configuration.load_full());
+        }
+        layers.extend(folder_model);
+        layers.push(self.inmem_configuration.load_full());
+        layers.push(self.policy_configuration.load_full());
+
+        let model = self.default_configuration.merge(&layers);
+
+        Self::resolve_value(&model, &attribute_name).cloned()
+    }
+
+    /// Returns the configuration of the innermost workspace folder that contains the resource.
+    fn get_folder_configuration(&self, resource: &Path) -> Option<Arc<ConfigurationModel>> {
+        self.folder_configurations
+            .load()
+            .iter()
+            .filter(|(folder, _)| resource.starts_with(folder))
+            .max_by_key(|(folder, _)| folder.components().count())
+            .map(|(_, model)| Arc::clone(model))
+    }
+
+    fn resolve_value<'a>(
+        model: &'a ConfigurationModel,
+        attribute_name: &ConfigurationKey,
+    ) -> Option<&'a Value> {
+        model.get_value(attribute_name).or_else(|| {
+            if !attribute_name.is_override() || attribute_name.name.is_empty() {
+                return None;
+            }
 
-        consolidated_model.get_value(attribute_name).cloned()
+            model.get_value(&attribute_name.base())
+        })
     }
 
-    pub fn inspect(&self, attribute_name: &AttributeName) -> InspectedConfigurationValue {
+    pub fn inspect(&self, attribute_name: &ConfigurationKey) -> InspectedConfigurationValue {
         let consolidated_model = self.get_consolidated_configuration();
 
-        let value = consolidated_model.get_value(attribute_name).cloned();
+        let value = Self::resolve_value(&consolidated_model, attribute_name).cloned();
 
         let mut inspected_value = InspectedConfigurationValue {
             attribute_name: attribute_name.to_string(),
             value,
             seen_in_overrides: Vec::new(),
             default_configuration: Arc::clone(&self.default_configuration),
-            policy_configuration: Arc::clone(&self.policy_configuration),
+            policy_configuration: self.policy_configuration.load_full(),
         };
 
-        if !attribute_name.is_with_override() {
+        if !attribute_name.is_override() && !attribute_name.name.is_empty() {
             for ident in consolidated_model.overrides.iter() {
-                if consolidated_model.overrides.contains(&format!(
-                    "$.[{}].{}",
-                    ident,
-                    attribute_name.must_get_name()
-                )) {
+                if consolidated_model
+                    .get(&ConfigurationKey::with_override(
+                        ident,
+                        attribute_name.name.clone(),
+                    ))
+                    .is_some()
+                {
                     inspected_value.seen_in_overrides.push(ident.to_string());
                 }
             }
@@ -318,6 +760,27 @@ impl Configuration {
         inspected_value
     }
 
+    /// Returns the problems found in the policy, user, workspace and workspace folder configuration sources.
+    pub fn diagnostics(&self) -> Vec<ConfigurationDiagnostic> {
+        let policy_configuration = self.policy_configuration.load();
+        let user_configuration = self.user_configuration.load();
+        let workspace_configuration = self.workspace_configuration.load();
+        let folder_configurations = self.folder_configurations.load();
+
+        policy_configuration
+            .diagnostics
+            .iter()
+            .chain(user_configuration.diagnostics.iter())
+            .chain(workspace_configuration.diagnostics.iter())
+            .chain(
+                folder_configurations
+                    .values()
+                    .flat_map(|model| model.diagnostics.iter()),
+            )
+            .cloned()
+            .collect()
+    }
+
     pub fn update_user_configuration(
         &self,
         new_model: Arc<ConfigurationModel>,
@@ -329,14 +792,102 @@ impl Configuration {
         diff
     }
 
-    fn compare(
+    pub fn update_workspace_configuration(
+        &self,
+        new_model: Arc<ConfigurationModel>,
+    ) -> ConfigurationDifference {
+        let diff = Self::compare(
+            self.workspace_configuration.load_full(),
+            Arc::clone(&new_model),
+        );
+        self.workspace_configuration.swap(new_model);
+        self.consolidated_configuration.swap(None);
+
+        diff
+    }
+
+    /// Sets the value of the attribute in the memory configuration, or removes it when `value` is `None`.
+    /// Memory values are not persisted and override the user and workspace values.
+    pub fn set_memory_value(
+        &self,
+        attribute_name: &ConfigurationKey,
+        value: Option<Value>,
+    ) -> ConfigurationDifference {
+        let mut new_model = ConfigurationModel::clone(&self.inmem_configuration.load());
+        let key = attribute_name.clone();
+        match value {
+            Some(value) => {
+                if let Some(ident) = &attribute_name.override_ident {
+                    if !new_model.overrides.contains(ident) {
+                        new_model.overrides.push(ident.clone());
+                    }
+                }
+                new_model.set_value(key, value);
+            }
+            None => {
+                new_model.remove_value(&key);
+            }
+        }
+
+        self.update_memory_configuration(Arc::new(new_model))
+    }
+
+    /// Removes all values from the memory configuration.
+    pub fn clear_memory_configuration(&self) -> ConfigurationDifference {
+        self.update_memory_configuration(Arc::new(ConfigurationModel::empty()))
+    }
+
+    pub fn update_memory_configuration(
+        &self,
+        new_model: Arc<ConfigurationModel>,
+    ) -> ConfigurationDifference {
+        let diff = Self::compare(self.inmem_configuration.load_full(), Arc::clone(&new_model));
+        self.inmem_configuration.swap(new_model);
+        self.consolidated_configuration.swap(None);
+
+        diff
+    }
+
+    /// Sets the configuration of a workspace folder. The folder values never change
+    /// the consolidated configuration, they are only read for the resources in the folder.
+    pub fn update_folder_configuration(
+        &self,
+        folder: PathBuf,
+        new_model: Arc<ConfigurationModel>,
+    ) -> ConfigurationDifference {
+        let mut folder_configurations = HashMap::clone(&self.folder_configurations.load());
+        let old_model = folder_configurations
+            .insert(folder, Arc::clone(&new_model))
+            .unwrap_or_else(|| Arc::new(ConfigurationModel::empty()));
+        self.folder_configurations
+            .store(Arc::new(folder_configurations));
+
+        Self::compare(old_model, new_model)
+    }
+
+    pub fn update_policy_configuration(
+        &self,
+        new_model: Arc<ConfigurationModel>,
+    ) -> ConfigurationDifference {
+        let diff = Self::compare(
+            self.policy_configuration.load_full(),
+            Arc::clone(&new_model),
+        );
+        self.policy_configuration.swap(new_model);
+        self.consolidated_configuration.swap(None);
+
+        diff
+    }
+
+    pub fn compare(
         old: Arc<ConfigurationModel>,
         new: Arc<ConfigurationModel>,
     ) -> ConfigurationDifference {
-        let old_names: HashSet<_> = old.names.iter().cloned().collect();
-        let new_names: HashSet<_> = new.names.iter().cloned().collect();
+        // Override values are only stored in the content, so its keys are compared instead of the names.
+        let old_names: HashSet<_> = old.content.keys().cloned().collect();
+        let new_names: HashSet<_> = new.content.keys().cloned().collect();
 
-        ConfigurationDifference {
+        let mut difference = ConfigurationDifference {
             added: new_names.difference(&old_names).cloned().collect(),
             removed: old_names.difference(&new_names).cloned().collect(),
             modified: old_names
@@ -349,7 +900,36 @@ impl Configuration {
                 )
                 .cloned()
                 .collect(),
+            overrides: Vec::new(),
+        };
+
+        for key in difference
+            .added
+            .iter()
+            .chain(difference.modified.iter())
+            .chain(difference.removed.iter())
+        {
+            if let Some(ident) = &key.override_ident {
+                if !difference.overrides.contains(ident) {
+                    difference.overrides.push(ident.clone());
+                }
+            }
         }
+        difference.overrides.sort();
+
+        difference
+    }
+
+    pub fn get_default_configuration(&self) -> Arc<ConfigurationModel> {
+        Arc::clone(&self.default_configuration)
+    }
+
+    pub fn get_user_configuration(&self) -> Arc<ConfigurationModel> {
+        self.user_configuration.load_full()
+    }
+
+    pub fn get_workspace_configuration(&self) -> Arc<ConfigurationModel> {
+        self.workspace_configuration.load_full()
     }
 
     pub fn get_consolidated_configuration(&self) -> Arc<ConfigurationModel> {
@@ -361,8 +941,10 @@ impl Configuration {
             .default_configuration
             .merge(&[
                 Arc::clone(&self.user_configuration.load_full()),
-                Arc::clone(&self.workspace_configuration),
-                Arc::clone(&self.inmem_configuration),
+                self.workspace_configuration.load_full(),
+                self.inmem_configuration.load_full(),
+                // Policy values are applied last, so they can't be overridden by any other layer.
+                self.policy_configuration.load_full(),
             ])
             .into();
 
@@ -379,48 +961,576 @@ mod tests {
     fn test_no_override_with_identifiers() {
         let name = attribute_name!(editor.fontSize);
         assert_eq!(name.override_ident, None);
-        assert_eq!(name.name, Some("editor.fontSize".to_string()));
+        assert_eq!(name.name, "editor.fontSize");
     }
 
     #[test]
     fn test_no_override_with_section_only() {
         let name = attribute_name!(editor);
         assert_eq!(name.override_ident, None);
-        assert_eq!(name.name, Some("editor".to_string()));
+        assert_eq!(name.name, "editor");
     }
 
     #[test]
     fn test_single_override_with_identifiers() {
         let name = attribute_name!([rust].editor.fontSize);
         assert_eq!(name.override_ident, Some("rust".to_string()));
-        assert_eq!(name.name, Some("editor.fontSize".to_string()));
+        assert_eq!(name.name, "editor.fontSize");
     }
 
     #[test]
     fn test_single_override_only() {
         let name = attribute_name!([rust]);
         assert_eq!(name.override_ident, Some("rust".to_string()));
-        assert_eq!(name.name, None);
+        assert_eq!(name.name, "");
     }
 
     #[test]
     fn test_no_override_with_multiple_identifiers() {
         let name = attribute_name!(config.window.size);
         assert_eq!(name.override_ident, None);
-        assert_eq!(name.name, Some("config.window.size".to_string()));
+        assert_eq!(name.name, "config.window.size");
     }
 
     #[test]
     fn test_single_override_with_multiple_identifiers() {
         let name = attribute_name!([javascript].config.window.size);
         assert_eq!(name.override_ident, Some("javascript".to_string()));
-        assert_eq!(name.name, Some("config.window.size".to_string()));
+        assert_eq!(name.name, "config.window.size");
     }
 
     #[test]
     fn test_single_override_only_with_no_identifiers() {
         let name = attribute_name!([typescript]);
         assert_eq!(name.override_ident, Some("typescript".to_string()));
-        assert_eq!(name.name, None);
+        assert_eq!(name.name, "");
+    }
+
+    fn section_test_configuration() -> super::Configuration {
+        let mut model = super::ConfigurationModel::empty();
+        model.set_value(
+            super::ConfigurationKey::new("editor.fontSize"),
+            serde_json::json!(12),
+        );
+        model.set_value(
+            super::ConfigurationKey::new("editor.tabSize"),
+            serde_json::json!(4),
+        );
+        model.set_value(
+            super::ConfigurationKey::new("editor.minimap.enabled"),
+            serde_json::json!(true),
+        );
+        model.set_value(
+            super::ConfigurationKey::new("editorial.mode"),
+            serde_json::json!("draft"),
+        );
+        model.set_value(
+            super::ConfigurationKey::with_override("rust", "editor.tabSize"),
+            serde_json::json!(2),
+        );
+        model.overrides.push("rust".to_string());
+        model.overrides.push("python".to_string());
+
+        super::Configuration::new(
+            std::sync::Arc::new(model),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+        )
+    }
+
+    #[test]
+    fn test_get_section() {
+        let configuration = section_test_configuration();
+
+        assert_eq!(
+            configuration.get_section(&attribute_name!(editor)),
+            Some(serde_json::json!({
+                "fontSize": 12,
+                "tabSize": 4,
+                "minimap": { "enabled": true }
+            }))
+        );
+        assert_eq!(
+            configuration.get_section(&attribute_name!(editor.minimap)),
+            Some(serde_json::json!({ "enabled": true }))
+        );
+        assert_eq!(
+            configuration.get_section(&attribute_name!(editor.fontSize)),
+            Some(serde_json::json!(12))
+        );
+        assert_eq!(configuration.get_section(&attribute_name!(window)), None);
+    }
+
+    #[test]
+    fn test_get_section_with_override() {
+        let configuration = section_test_configuration();
+
+        assert_eq!(
+            configuration.get_section(&attribute_name!([rust].editor)),
+            Some(serde_json::json!({
+                "fontSize": 12,
+                "tabSize": 2,
+                "minimap": { "enabled": true }
+            }))
+        );
+        assert_eq!(
+            configuration.get_section(&attribute_name!([python].editor.tabSize)),
+            Some(serde_json::json!(4))
+        );
+    }
+
+    #[test]
+    fn test_get_value_with_override_fallback() {
+        let configuration = section_test_configuration();
+
+        assert_eq!(
+            configuration.get_value(&attribute_name!([rust].editor.tabSize)),
+            Some(serde_json::json!(2))
+        );
+        assert_eq!(
+            configuration.get_value(&attribute_name!([rust].editor.fontSize)),
+            Some(serde_json::json!(12))
+        );
+        assert_eq!(
+            configuration.get_value_for_override(&attribute_name!(editor.tabSize), Some("python")),
+            Some(serde_json::json!(4))
+        );
+        assert_eq!(
+            configuration.get_value_for_override(&attribute_name!(editor.tabSize), None),
+            Some(serde_json::json!(4))
+        );
+    }
+
+    #[test]
+    fn test_override_in_later_layer_wins() {
+        let mut default_model = super::ConfigurationModel::empty();
+        default_model.set_value(
+            super::ConfigurationKey::new("editor.tabSize"),
+            serde_json::json!(4),
+        );
+
+        let mut user_model = super::ConfigurationModel::empty();
+        user_model.set_value(
+            super::ConfigurationKey::with_override("rust", "editor.tabSize"),
+            serde_json::json!(2),
+        );
+        user_model.overrides.push("rust".to_string());
+
+        let mut workspace_model = super::ConfigurationModel::empty();
+        workspace_model.set_value(
+            super::ConfigurationKey::new("editor.tabSize"),
+            serde_json::json!(8),
+        );
+
+        let configuration = super::Configuration::new(
+            std::sync::Arc::new(default_model),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+            std::sync::Arc::new(user_model),
+            std::sync::Arc::new(workspace_model),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+        );
+
+        assert_eq!(
+            configuration.get_value(&attribute_name!(editor.tabSize)),
+            Some(serde_json::json!(8))
+        );
+        assert_eq!(
+            configuration.get_value(&attribute_name!([rust].editor.tabSize)),
+            Some(serde_json::json!(2))
+        );
+    }
+
+    #[test]
+    fn test_inspect_seen_in_overrides() {
+        let configuration = section_test_configuration();
+
+        let inspected_value = configuration.inspect(&attribute_name!(editor.tabSize));
+        assert_eq!(inspected_value.value(), &Some(serde_json::json!(4)));
+        assert_eq!(
+            inspected_value.seen_in_overrides(),
+            &vec!["rust".to_string()]
+        );
+
+        let inspected_value = configuration.inspect(&attribute_name!(editor.fontSize));
+        assert!(inspected_value.seen_in_overrides().is_empty());
+    }
+
+    #[test]
+    fn test_policy_value_wins_over_other_layers() {
+        let mut default_model = super::ConfigurationModel::empty();
+        default_model.set_value(
+            super::ConfigurationKey::new("editor.lineHeight"),
+            serde_json::json!(20),
+        );
+
+        let mut policy_model = super::ConfigurationModel::empty();
+        policy_model.set_policy_value(
+            super::ConfigurationKey::new("editor.lineHeight"),
+            serde_json::json!(45),
+            "editorLineHeightPolicy".to_string(),
+        );
+
+        let mut user_model = super::ConfigurationModel::empty();
+        user_model.set_value(
+            super::ConfigurationKey::new("editor.lineHeight"),
+            serde_json::json!(30),
+        );
+
+        let configuration = super::Configuration::new(
+            std::sync::Arc::new(default_model),
+            std::sync::Arc::new(policy_model),
+            std::sync::Arc::new(user_model),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+        );
+
+        assert_eq!(
+            configuration.get_value(&attribute_name!(editor.lineHeight)),
+            Some(serde_json::json!(45))
+        );
+
+        let inspected_value = configuration.inspect(&attribute_name!(editor.lineHeight));
+        assert_eq!(
+            inspected_value.get_policy_name(&attribute_name!(editor.lineHeight)),
+            Some("editorLineHeightPolicy")
+        );
+        assert_eq!(
+            inspected_value.get_policy_name(&attribute_name!(editor.fontSize)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_policy_value_wins_over_override_values() {
+        use crate::{
+            configuration_parser::ConfigurationParser,
+            configuration_registry::{
+                test_utils::registry_with, ConfigurationPropertySchema, PropertyMap,
+            },
+            property_key,
+        };
+        use platform_core::context_v2::{Context, ContextCell};
+        use platform_core::platform::cross::client::CrossPlatformClient;
+        use std::rc::Rc;
+
+        let ctx_cell = ContextCell::new(Rc::new(CrossPlatformClient::new()));
+        let ctx: &mut Context = &mut ctx_cell.borrow_mut();
+
+        let mut properties = PropertyMap::new();
+        properties.insert(
+            property_key!(editor.lineHeight),
+            ConfigurationPropertySchema::default(),
+        );
+        properties.insert(
+            property_key!(editor.fontSize),
+            ConfigurationPropertySchema::default(),
+        );
+        // Registers `rust` as an override identifier.
+        properties.insert(
+            property_key!([rust].editor.fontSize),
+            ConfigurationPropertySchema::default(),
+        );
+        let registry = registry_with(ctx, "editor", properties);
+
+        let user_model = ConfigurationParser::new(registry).parse(
+            ctx,
+            r#"{
+    "[rust]": {
+        "editor.lineHeight": 30,
+        "editor.fontSize": 14
+    }
+}"#,
+        );
+        assert!(user_model.diagnostics().is_empty());
+
+        let mut policy_model = super::ConfigurationModel::empty();
+        policy_model.set_policy_value(
+            super::ConfigurationKey::new("editor.lineHeight"),
+            serde_json::json!(45),
+            "editorLineHeightPolicy".to_string(),
+        );
+
+        let configuration = super::Configuration::new(
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+            std::sync::Arc::new(policy_model),
+            std::sync::Arc::new(user_model),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+        );
+
+        assert_eq!(
+            configuration.get_value(&attribute_name!([rust].editor.lineHeight)),
+            Some(serde_json::json!(45))
+        );
+        assert_eq!(
+            configuration.get_value(&attribute_name!([rust].editor.fontSize)),
+            Some(serde_json::json!(14))
+        );
+    }
+
+    #[test]
+    fn test_get_value_with_overrides_for_resource() {
+        use super::{ConfigurationOverrides, ConfigurationScope};
+        use std::path::PathBuf;
+
+        let mut default_model = super::ConfigurationModel::empty();
+        default_model.set_value(
+            super::ConfigurationKey::new("editor.tabSize"),
+            serde_json::json!(4),
+        );
+        default_model.set_scope(
+            super::ConfigurationKey::new("editor.tabSize"),
+            ConfigurationScope::Resource,
+        );
+        default_model.set_value(
+            super::ConfigurationKey::new("window.zoom"),
+            serde_json::json!(1),
+        );
+        default_model.set_scope(
+            super::ConfigurationKey::new("window.zoom"),
+            ConfigurationScope::Window,
+        );
+
+        let mut workspace_model = super::ConfigurationModel::empty();
+        workspace_model.set_value(
+            super::ConfigurationKey::new("editor.tabSize"),
+            serde_json::json!(2),
+        );
+        workspace_model.set_value(
+            super::ConfigurationKey::new("window.zoom"),
+            serde_json::json!(2),
+        );
+
+        let configuration = super::Configuration::new(
+            std::sync::Arc::new(default_model),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+            std::sync::Arc::new(workspace_model),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+        )
+        .with_workspace_root(Some(PathBuf::from("/projects/moss")));
+
+        let in_workspace = ConfigurationOverrides {
+            override_ident: None,
+            resource: Some(PathBuf::from("/projects/moss/src/main.rs")),
+        };
+        let outside_workspace = ConfigurationOverrides {
+            override_ident: None,
+            resource: Some(PathBuf::from("/tmp/scratch.rs")),
+        };
+
+        assert_eq!(
+            configuration.get_value_with_overrides(&attribute_name!(editor.tabSize), &in_workspace),
+            Some(serde_json::json!(2))
+        );
+        assert_eq!(
+            configuration
+                .get_value_with_overrides(&attribute_name!(editor.tabSize), &outside_workspace),
+            Some(serde_json::json!(4))
+        );
+        // Window settings apply to every resource opened in the window.
+        assert_eq!(
+            configuration
+                .get_value_with_overrides(&attribute_name!(window.zoom), &outside_workspace),
+            Some(serde_json::json!(2))
+        );
+    }
+
+    #[test]
+    fn test_get_value_with_overrides_for_folder_resource() {
+        use super::{ConfigurationOverrides, ConfigurationScope};
+        use std::path::PathBuf;
+
+        let mut default_model = super::ConfigurationModel::empty();
+        default_model.set_value(
+            super::ConfigurationKey::new("editor.tabSize"),
+            serde_json::json!(4),
+        );
+        default_model.set_scope(
+            super::ConfigurationKey::new("editor.tabSize"),
+            ConfigurationScope::Resource,
+        );
+
+        let mut workspace_model = super::ConfigurationModel::empty();
+        workspace_model.set_value(
+            super::ConfigurationKey::new("editor.tabSize"),
+            serde_json::json!(2),
+        );
+
+        let configuration = super::Configuration::new(
+            std::sync::Arc::new(default_model),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+            std::sync::Arc::new(workspace_model),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+        )
+        .with_workspace_root(Some(PathBuf::from("/projects/moss")));
+
+        let mut folder_model = super::ConfigurationModel::empty();
+        folder_model.set_value(
+            super::ConfigurationKey::new("editor.tabSize"),
+            serde_json::json!(8),
+        );
+        configuration.update_folder_configuration(
+            PathBuf::from("/projects/api"),
+            std::sync::Arc::new(folder_model),
+        );
+
+        let overrides = |resource: &str| ConfigurationOverrides {
+            override_ident: None,
+            resource: Some(PathBuf::from(resource)),
+        };
+
+        assert_eq!(
+            configuration.get_value_with_overrides(
+                &attribute_name!(editor.tabSize),
+                &overrides("/projects/api/src/lib.rs")
+            ),
+            Some(serde_json::json!(8))
+        );
+        assert_eq!(
+            configuration.get_value_with_overrides(
+                &attribute_name!(editor.tabSize),
+                &overrides("/projects/moss/src/main.rs")
+            ),
+            Some(serde_json::json!(2))
+        );
+        assert_eq!(
+            configuration.get_value(&attribute_name!(editor.tabSize)),
+            Some(serde_json::json!(2))
+        );
+    }
+
+    #[test]
+    fn test_set_and_clear_memory_values() {
+        use super::{ConfigurationChangeEvent, ConfigurationTarget};
+
+        let mut user_model = super::ConfigurationModel::empty();
+        user_model.set_value(
+            super::ConfigurationKey::new("editor.fontSize"),
+            serde_json::json!(12),
+        );
+
+        let configuration = super::Configuration::new(
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+            std::sync::Arc::new(user_model),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+            std::sync::Arc::new(super::ConfigurationModel::empty()),
+        );
+
+        let diff = configuration.set_memory_value(
+            &attribute_name!(editor.fontSize),
+            Some(serde_json::json!(16)),
+        );
+        assert_eq!(
+            diff.added,
+            vec![super::ConfigurationKey::new("editor.fontSize")]
+        );
+        assert_eq!(
+            configuration.get_value(&attribute_name!(editor.fontSize)),
+            Some(serde_json::json!(16))
+        );
+
+        let diff = configuration.set_memory_value(
+            &attribute_name!(editor.fontSize),
+            Some(serde_json::json!(18)),
+        );
+        assert_eq!(
+            diff.modified,
+            vec![super::ConfigurationKey::new("editor.fontSize")]
+        );
+
+        configuration
+            .set_memory_value(&attribute_name!(editor.tabSize), Some(serde_json::json!(2)));
+        let diff = configuration.set_memory_value(&attribute_name!(editor.tabSize), None);
+        assert_eq!(
+            diff.removed,
+            vec![super::ConfigurationKey::new("editor.tabSize")]
+        );
+        assert_eq!(
+            configuration.get_value(&attribute_name!(editor.tabSize)),
+            None
+        );
+
+        let event = ConfigurationChangeEvent::new(
+            ConfigurationTarget::Memory,
+            configuration.clear_memory_configuration(),
+        );
+        assert_eq!(event.affected_keys(), &["editor.fontSize".to_string()]);
+        assert_eq!(
+            configuration.get_value(&attribute_name!(editor.fontSize)),
+            Some(serde_json::json!(12))
+        );
+    }
+
+    #[test]
+    fn test_change_event_affects_configuration() {
+        let event = super::ConfigurationChangeEvent::new(
+            super::ConfigurationTarget::User,
+            super::ConfigurationDifference {
+                added: vec![super::ConfigurationKey::new("editor.fontSize")],
+                modified: vec![super::ConfigurationKey::new("window.zoom")],
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(event.affected_keys(), &["editor.fontSize", "window.zoom"]);
+        assert!(event.affects_configuration("editor"));
+        assert!(event.affects_configuration("editor.fontSize"));
+        assert!(!event.affects_configuration("edit"));
+        assert!(!event.affects_configuration("editor.tabSize"));
+    }
+
+    #[test]
+    fn test_change_event_with_overrides() {
+        let mut old_model = super::ConfigurationModel::empty();
+        old_model.set_value(
+            super::ConfigurationKey::new("editor.fontSize"),
+            serde_json::json!(12),
+        );
+        old_model.set_value(
+            super::ConfigurationKey::with_override("rust", "editor.tabSize"),
+            serde_json::json!(4),
+        );
+
+        let mut new_model = super::ConfigurationModel::empty();
+        new_model.set_value(
+            super::ConfigurationKey::new("editor.fontSize"),
+            serde_json::json!(12),
+        );
+        new_model.set_value(
+            super::ConfigurationKey::with_override("rust", "editor.tabSize"),
+            serde_json::json!(2),
+        );
+        new_model.set_value(
+            super::ConfigurationKey::with_override("python", "editor.fontSize"),
+            serde_json::json!(14),
+        );
+
+        let difference = super::Configuration::compare(
+            std::sync::Arc::new(old_model),
+            std::sync::Arc::new(new_model),
+        );
+        assert_eq!(difference.overrides, vec!["python", "rust"]);
+
+        let event =
+            super::ConfigurationChangeEvent::new(super::ConfigurationTarget::User, difference);
+
+        assert_eq!(
+            event.affected_keys(),
+            &["editor.fontSize", "editor.tabSize"]
+        );
+        assert_eq!(
+            event.affected_overrides().collect::<Vec<_>>(),
+            vec!["python", "rust"]
+        );
+        assert!(event.affects_configuration("editor.tabSize"));
+        assert!(event.affects("editor.tabSize", Some("rust")));
+        assert!(event.affects("editor", Some("python")));
+        assert!(!event.affects("editor.tabSize", Some("python")));
+        assert!(!event.affects("editor.tabSize", None));
     }
 }
@@ -1,40 +1,110 @@
-use anyhow::Result;
-use hashbrown::HashMap;
 use lazy_regex::{Lazy, Regex};
 use platform_core::base::collection::extend::Extend;
 use platform_core::context_v2::{atom::Atom, Context};
 use radix_trie::{Trie, TrieCommon};
-use serde_json::Value;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 
 use crate::{
-    configuration_model::{AttributeName, ConfigurationModel},
-    configuration_registry::ConfigurationRegistry,
+    configuration_diagnostic::{ConfigurationDiagnostic, DiagnosticSpan},
+    configuration_key::ConfigurationKey,
+    configuration_migration::MigratedSetting,
+    configuration_model::{ConfigurationModel, ConfigurationTarget},
+    configuration_registry::{ConfigurationPropertySchema, ConfigurationRegistry},
+    jsonc::{self, Node, NodeKind, ParseResult},
 };
 
 static OVERRIDE_PROPERTY_REGEX: &'static Lazy<Regex> = regex!(r"^(\[.*\])+$");
 
 pub struct ConfigurationParser {
     registry: Atom<ConfigurationRegistry>,
+    /// The target the parsed content is read for. Settings with a scope the target
+    /// can't set, e.g. application-wide settings in a workspace settings file, are dropped.
+    target: Option<ConfigurationTarget>,
+    /// In restricted mode the settings only allowed for trusted sources are dropped,
+    /// see `ConfigurationPropertySchema::allow_for_only_restricted_source`.
+    restricted_mode: AtomicBool,
+}
+
+/// A setting property of a settings file, named by its dotted name even if it is written
+/// in a nested section, see `ConfigurationParser::flatten_property`.
+struct SettingProperty<'a> {
+    name: String,
+    node: &'a Node,
 }
 
 struct ConfigurationOverride {
     ident: String,
-    attribute_names: Vec<String>,
-    content: Trie<String, serde_json::Value>,
+    attribute_names: Vec<ConfigurationKey>,
+    content: Trie<ConfigurationKey, serde_json::Value>,
 }
 
 impl ConfigurationParser {
     pub fn new(registry: Atom<ConfigurationRegistry>) -> Self {
-        Self { registry }
+        Self {
+            registry,
+            target: None,
+            restricted_mode: AtomicBool::new(false),
+        }
+    }
+
+    /// Settings with a scope that the target can't set are dropped when parsing
+    /// and reported as diagnostics, see `ConfigurationTarget::scopes`.
+    pub fn with_target(mut self, target: ConfigurationTarget) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// Used to read the settings of untrusted workspaces.
+    pub fn with_restricted_mode(self, restricted_mode: bool) -> Self {
+        self.set_restricted_mode(restricted_mode);
+        self
+    }
+
+    /// Changes the mode for the next parsing, e.g. when the user trusts the workspace.
+    pub fn set_restricted_mode(&self, restricted_mode: bool) {
+        self.restricted_mode
+            .store(restricted_mode, Ordering::Relaxed);
+    }
+
+    pub fn is_restricted_mode(&self) -> bool {
+        self.restricted_mode.load(Ordering::Relaxed)
     }
 
-    pub fn parse(&self, ctx: &mut Context, content: &str) -> Result<ConfigurationModel> {
-        let raw_content: HashMap<String, Value> = serde_json::from_str(content)?;
+    /// Parses the content of a settings file, which may contain comments and trailing commas.
+    /// Problems never fail the whole parsing: the resulting model contains every attribute
+    /// that could be read and reports the rest through `ConfigurationModel::diagnostics`.
+    pub fn parse(&self, ctx: &mut Context, content: &str) -> ConfigurationModel {
+        let ParseResult { root, errors } = jsonc::parse_tree(content);
         let mut model = ConfigurationModel::empty();
+        model
+            .diagnostics
+            .extend(errors.into_iter().map(ConfigurationDiagnostic::from));
+
+        let root = match root {
+            Some(root) if root.kind == NodeKind::Object => root,
+            Some(root) => {
+                model.diagnostics.push(
+                    ConfigurationDiagnostic::error("The settings must be an object")
+                        .with_span(DiagnosticSpan::from_node(content, &root)),
+                );
+
+                return model;
+            }
+            None => return model,
+        };
+
+        let mut properties = Vec::new();
+        for property in &root.children {
+            self.flatten_property(ctx, None, property, &mut properties);
+        }
 
-        for (attribute_name, value) in &raw_content {
-            if OVERRIDE_PROPERTY_REGEX.is_match(attribute_name) {
-                if let Some(override_definition) = self.process_override(ctx, attribute_name, value)
+        for property in &properties {
+            if OVERRIDE_PROPERTY_REGEX.is_match(&property.name) {
+                if let Some(override_definition) =
+                    self.process_override(ctx, content, property.node, &mut model)
                 {
                     model.overrides.push(override_definition.ident);
                     model.content.extend(override_definition.content.iter());
@@ -43,59 +113,238 @@ impl ConfigurationParser {
                 continue;
             }
 
-            if self.inspect_attribute(ctx, attribute_name) {
-                model.set_value(AttributeName::format(attribute_name), value.clone());
+            let Some((attribute_name, value, migrated)) =
+                self.migrate_attribute(ctx, content, property, &properties, None, &mut model)
+            else {
+                continue;
+            };
+
+            if let Some(schema) = self.inspect_attribute(
+                ctx,
+                content,
+                &attribute_name,
+                property.node,
+                &mut model.diagnostics,
+            ) {
+                if let Some(migrated) = migrated {
+                    Self::record_migration(content, property, migrated, &mut model);
+                }
+
+                let key = ConfigurationKey::new(attribute_name);
+                if let Some(scope) = &schema.scope {
+                    model.set_scope(key.clone(), scope.clone());
+                }
+
+                model.set_value(key, value);
             }
         }
 
-        Ok(model)
+        model
     }
 
-    // TODO: return diags
-    // TODO: use logs, not println
-    fn inspect_attribute(&self, ctx: &mut Context, attribute_name: &str) -> bool {
-        let configuration_properties = self.registry.read(ctx).properties();
+    /// Lists the settings set by the property. The properties of a nested section,
+    /// e.g. `{ "window": { "defaultWidth": 1200 } }`, are listed with their dotted names,
+    /// e.g. `window.defaultWidth`, as if they were written that way. The value of a registered
+    /// setting is never descended into, so object-typed settings are kept intact.
+    fn flatten_property<'a>(
+        &self,
+        ctx: &mut Context,
+        section: Option<&str>,
+        property: &'a Node,
+        properties: &mut Vec<SettingProperty<'a>>,
+    ) {
+        let Some(key) = property.property_key() else {
+            return;
+        };
+        let attribute_name = match section {
+            Some(section) => format!("{}.{}", section, key),
+            None => key.to_string(),
+        };
+
+        let is_section = match property.property_value() {
+            Some(value) if value.kind == NodeKind::Object => {
+                let registry = self.registry.read(ctx);
+                registry.get_property(&attribute_name).is_none()
+                    && registry.get_migration(&attribute_name).is_none()
+                    && registry.has_section(&attribute_name)
+            }
+            _ => false,
+        };
+        if !is_section {
+            properties.push(SettingProperty {
+                name: attribute_name,
+                node: property,
+            });
+            return;
+        }
 
-        match configuration_properties.get(attribute_name) {
-            Some(registered_property) => {
-                if registered_property.is_protected_from_contribution() {
-                    println!(
-                        "Property `{}` is protected from contribution",
-                        attribute_name
-                    );
-                    return false;
-                }
+        for nested_property in &property.children[1].children {
+            self.flatten_property(ctx, Some(&attribute_name), nested_property, properties);
+        }
+    }
 
-                true
+    /// Reads a deprecated setting as the setting that replaces it, see `ConfigurationMigration`.
+    /// Returns the name and the value the property is read as, together with the migration
+    /// applied to it, or `None` if it is dropped.
+    fn migrate_attribute(
+        &self,
+        ctx: &mut Context,
+        content: &str,
+        property: &SettingProperty,
+        siblings: &[SettingProperty],
+        override_ident: Option<&str>,
+        model: &mut ConfigurationModel,
+    ) -> Option<(String, serde_json::Value, Option<MigratedSetting>)> {
+        let attribute_name = property.name.as_str();
+        let value = property.node.property_value()?.to_value();
+
+        let Some(migration) = self.registry.read(ctx).get_migration(attribute_name) else {
+            return Some((attribute_name.to_string(), value, None));
+        };
+        let span = DiagnosticSpan::from_node(content, &property.node.children[0]);
+
+        // A value of the new setting in the same object wins over the migrated one.
+        let is_new_key_set = siblings.iter().any(|other| other.name == migration.new_key);
+        if is_new_key_set {
+            model.diagnostics.push(
+                ConfigurationDiagnostic::warning(format!(
+                    "Setting `{}` is deprecated and ignored because `{}` is set",
+                    attribute_name, migration.new_key
+                ))
+                .with_key(attribute_name)
+                .with_span(span),
+            );
+
+            return None;
+        }
+
+        let Some(value) = migration.migrate(value) else {
+            model.diagnostics.push(
+                ConfigurationDiagnostic::warning(format!(
+                    "The value of the deprecated setting `{}` can't be migrated to `{}`",
+                    attribute_name, migration.new_key
+                ))
+                .with_key(attribute_name)
+                .with_span(span),
+            );
+
+            return None;
+        };
+
+        let migrated = MigratedSetting {
+            key: attribute_name.to_string(),
+            new_key: migration.new_key.clone(),
+            override_ident: override_ident.map(str::to_string),
+            value: value.clone(),
+        };
+
+        Some((migration.new_key.clone(), value, Some(migrated)))
+    }
+
+    /// Records a migration once the setting it migrates to has been accepted,
+    /// so a migration to a setting that can't be set here is never written back.
+    fn record_migration(
+        content: &str,
+        property: &SettingProperty,
+        migrated: MigratedSetting,
+        model: &mut ConfigurationModel,
+    ) {
+        model.diagnostics.push(
+            ConfigurationDiagnostic::info(format!(
+                "Setting `{}` is deprecated and was migrated to `{}`",
+                migrated.key, migrated.new_key
+            ))
+            .with_key(&migrated.key)
+            .with_span(DiagnosticSpan::from_node(
+                content,
+                &property.node.children[0],
+            )),
+        );
+        model.migrated.push(migrated);
+    }
+
+    /// Returns the schema of the property if its value can be read.
+    fn inspect_attribute(
+        &self,
+        ctx: &mut Context,
+        content: &str,
+        attribute_name: &str,
+        property: &Node,
+        diagnostics: &mut Vec<ConfigurationDiagnostic>,
+    ) -> Option<Arc<ConfigurationPropertySchema>> {
+        let registry = self.registry.read(ctx);
+
+        let message = match registry.get_property(attribute_name) {
+            Some(registered_property) if registered_property.is_protected_from_contribution() => {
+                format!(
+                    "Setting `{}` is protected from contribution and can't be set here",
+                    attribute_name
+                )
             }
-            None => {
-                println!("Unknown property `{}` was detected", attribute_name);
-                return false;
+            Some(registered_property)
+                if registered_property.schema.allow_for_only_restricted_source
+                    && self.is_restricted_mode() =>
+            {
+                format!(
+                    "Setting `{}` is ignored because the workspace is not trusted",
+                    attribute_name
+                )
             }
-        }
+            Some(registered_property) => match (&self.target, &registered_property.schema.scope) {
+                (Some(target), Some(scope)) if !target.scopes().contains(scope) => format!(
+                    "Setting `{}` has {:?} scope and can't be set in {} settings",
+                    attribute_name, scope, target
+                ),
+                _ => return Some(Arc::clone(&registered_property.schema)),
+            },
+            None => format!("Unknown configuration setting `{}`", attribute_name),
+        };
+
+        diagnostics.push(
+            ConfigurationDiagnostic::warning(message)
+                .with_key(attribute_name)
+                .with_span(DiagnosticSpan::from_node(content, &property.children[0])),
+        );
+
+        None
     }
 
     fn process_override(
         &self,
         ctx: &mut Context,
-        attribute_name: &str,
-        value: &Value,
+        content: &str,
+        property: &Node,
+        model: &mut ConfigurationModel,
     ) -> Option<ConfigurationOverride> {
-        let content = if let Value::Object(obj) = value {
-            obj
-        } else {
-            // If the override is not an object, then we don't want to handle it in any way.
+        let attribute_name = property.property_key()?;
+        let value = property.property_value()?;
+
+        if value.kind != NodeKind::Object {
+            model.diagnostics.push(
+                ConfigurationDiagnostic::warning(format!(
+                    "The value of the override `{}` must be an object",
+                    attribute_name
+                ))
+                .with_key(attribute_name)
+                .with_span(DiagnosticSpan::from_node(content, value)),
+            );
+
             return None;
-        };
+        }
 
         let override_identifiers = self.registry.read(ctx).override_identifiers();
         let formatted_identifier = attribute_name.trim_matches(|c| c == '[' || c == ']');
 
         if override_identifiers.get(formatted_identifier).is_none() {
-            println!(
-                "Unknown override identifier `{}` was detected",
-                formatted_identifier
+            model.diagnostics.push(
+                ConfigurationDiagnostic::warning(format!(
+                    "Unknown override identifier `{}`",
+                    formatted_identifier
+                ))
+                .with_key(attribute_name)
+                .with_span(DiagnosticSpan::from_node(content, &property.children[0])),
             );
+
             return None;
         }
 
@@ -105,16 +354,470 @@ impl ConfigurationParser {
             content: Trie::new(),
         };
 
-        for (attribute_name, value) in content {
-            if self.inspect_attribute(ctx, attribute_name) {
-                // let formatted_key = format!("$.[{}].{}", formatted_identifier, attribute_name);
-                let formatted_key =
-                    AttributeName::format_with_override(attribute_name, formatted_identifier);
-                result.content.insert(formatted_key.clone(), value.clone());
-                result.attribute_names.push(formatted_key);
+        let mut properties = Vec::new();
+        for override_property in &value.children {
+            self.flatten_property(ctx, None, override_property, &mut properties);
+        }
+
+        for override_property in &properties {
+            let Some((attribute_name, value, migrated)) = self.migrate_attribute(
+                ctx,
+                content,
+                override_property,
+                &properties,
+                Some(formatted_identifier),
+                model,
+            ) else {
+                continue;
+            };
+
+            if let Some(schema) = self.inspect_attribute(
+                ctx,
+                content,
+                &attribute_name,
+                override_property.node,
+                &mut model.diagnostics,
+            ) {
+                if let Some(migrated) = migrated {
+                    Self::record_migration(content, override_property, migrated, model);
+                }
+
+                let key = ConfigurationKey::with_override(formatted_identifier, attribute_name);
+                if let Some(scope) = &schema.scope {
+                    model.set_scope(key.clone(), scope.clone());
+                }
+
+                result.content.insert(key.clone(), value);
+                result.attribute_names.push(key);
             }
         }
 
         Some(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attribute_name,
+        configuration_diagnostic::DiagnosticSeverity,
+        configuration_migration::ConfigurationMigration,
+        configuration_registry::{
+            test_utils::node, ConfigurationNode, ConfigurationNodeType, ConfigurationScope,
+            PropertyMap,
+        },
+        property_key,
+    };
+    use platform_core::context_v2::{AnyContext, ContextCell};
+    use platform_core::platform::cross::client::CrossPlatformClient;
+    use serde_json::json;
+    use std::rc::Rc;
+
+    fn parse(content: &str) -> ConfigurationModel {
+        parse_for_target(content, None)
+    }
+
+    fn parse_for_target(content: &str, target: Option<ConfigurationTarget>) -> ConfigurationModel {
+        parse_with(content, target, false)
+    }
+
+    fn parse_with(
+        content: &str,
+        target: Option<ConfigurationTarget>,
+        restricted_mode: bool,
+    ) -> ConfigurationModel {
+        let ctx_cell = ContextCell::new(Rc::new(CrossPlatformClient::new()));
+        let ctx: &mut Context = &mut ctx_cell.borrow_mut();
+
+        let registry = ctx.create_atom(|_| {
+            let mut registry = ConfigurationRegistry::new();
+            let mut properties = PropertyMap::new();
+            properties.insert(
+                property_key!(editor.fontSize),
+                ConfigurationPropertySchema::default(),
+            );
+            properties.insert(
+                property_key!(editor.tabSize),
+                ConfigurationPropertySchema::default(),
+            );
+            properties.insert(
+                property_key!(editor.minimap.enabled),
+                ConfigurationPropertySchema::default(),
+            );
+            properties.insert(
+                property_key!(editor.quickSuggestions),
+                ConfigurationPropertySchema {
+                    typ: Some(ConfigurationNodeType::Object),
+                    ..Default::default()
+                },
+            );
+            properties.insert(
+                property_key!(editor.formatterPath),
+                ConfigurationPropertySchema {
+                    allow_for_only_restricted_source: true,
+                    ..Default::default()
+                },
+            );
+
+            properties.insert(
+                property_key!(editor.formatOnSave),
+                ConfigurationPropertySchema {
+                    scope: Some(ConfigurationScope::Resource),
+                    ..Default::default()
+                },
+            );
+            properties.insert(
+                property_key!(editor.gpuAcceleration),
+                ConfigurationPropertySchema {
+                    scope: Some(ConfigurationScope::Machine),
+                    ..Default::default()
+                },
+            );
+
+            registry.register_configuration(&node("editor", properties));
+            registry.register_migration(ConfigurationMigration::rename(
+                "editor.size",
+                "editor.fontSize",
+            ));
+            registry.register_migration(ConfigurationMigration::transform(
+                "editor.tabWidth",
+                "editor.tabSize",
+                |value| {
+                    value
+                        .as_str()
+                        .and_then(|value| value.parse::<u64>().ok())
+                        .map(|value| json!(value))
+                },
+            ));
+
+            let mut properties = PropertyMap::new();
+            properties.insert(
+                property_key!(update.mode),
+                ConfigurationPropertySchema::default(),
+            );
+
+            registry.register_configuration(&ConfigurationNode {
+                scope: Some(ConfigurationScope::Application),
+                ..node("update", properties)
+            });
+            registry.register_migration(ConfigurationMigration::rename(
+                "update.channel",
+                "update.mode",
+            ));
+
+            registry
+        });
+
+        let parser = ConfigurationParser::new(registry).with_restricted_mode(restricted_mode);
+        match target {
+            Some(target) => parser.with_target(target).parse(ctx, content),
+            None => parser.parse(ctx, content),
+        }
+    }
+
+    #[test]
+    fn test_parse_jsonc() {
+        let model = parse(
+            r#"{
+    // Comments are allowed
+    "editor.fontSize": 14, /* and block comments too */
+    "editor.tabSize": 2,
+}"#,
+        );
+
+        assert!(model.diagnostics().is_empty());
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.fontSize)),
+            Some(&json!(14))
+        );
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.tabSize)),
+            Some(&json!(2))
+        );
+    }
+
+    #[test]
+    fn test_parse_nested_sections() {
+        let model = parse(
+            r#"{
+    "editor": {
+        "fontSize": 14,
+        "minimap": { "enabled": false },
+        "quickSuggestions": { "comments": true },
+        "size": 12,
+        "unknown": 1
+    },
+    "editor.tabSize": 2
+}"#,
+        );
+
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.fontSize)),
+            Some(&json!(14))
+        );
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.minimap.enabled)),
+            Some(&json!(false))
+        );
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.quickSuggestions)),
+            Some(&json!({ "comments": true }))
+        );
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.tabSize)),
+            Some(&json!(2))
+        );
+
+        // The deprecated setting is ignored, the new one is set in the same object.
+        let messages: Vec<_> = model
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Setting `editor.size` is deprecated and ignored because `editor.fontSize` is set",
+                "Unknown configuration setting `editor.unknown`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_returns_partial_model_on_errors() {
+        let model = parse(
+            r#"{
+    "editor.fontSize": ,
+    "editor.tabSize": 2
+"#,
+        );
+
+        assert_eq!(model.diagnostics().len(), 2);
+        assert!(model.diagnostics().iter().all(|d| d.is_error()));
+        assert_eq!(model.diagnostics()[0].span.as_ref().unwrap().line, 2);
+        assert_eq!(model.get_value(&attribute_name!(editor.fontSize)), None);
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.tabSize)),
+            Some(&json!(2))
+        );
+    }
+
+    #[test]
+    fn test_parse_non_object_root() {
+        let model = parse("[1, 2]");
+
+        assert_eq!(model.diagnostics().len(), 1);
+        assert!(model.get_attribute_names().is_empty());
+    }
+
+    #[test]
+    fn test_parse_diagnostics() {
+        let model = parse(
+            r#"{
+    "editor.unknown": 1,
+    "[rust]": { "editor.fontSize": 12 },
+    "[unknown]": { "editor.fontSize": 12 }
+}"#,
+        );
+
+        let diagnostics: Vec<_> = model
+            .diagnostics()
+            .iter()
+            .map(|d| {
+                (
+                    d.severity,
+                    d.key.as_deref(),
+                    d.span.as_ref().map(|span| (span.line, span.column)),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            diagnostics,
+            vec![
+                (
+                    DiagnosticSeverity::Warning,
+                    Some("editor.unknown"),
+                    Some((2, 5))
+                ),
+                (DiagnosticSeverity::Warning, Some("[rust]"), Some((3, 5))),
+                (DiagnosticSeverity::Warning, Some("[unknown]"), Some((4, 5))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_for_target() {
+        let content = r#"{
+    "editor.fontSize": 14,
+    "update.mode": "manual"
+}"#;
+
+        let model = parse(content);
+        assert!(model.diagnostics().is_empty());
+        assert_eq!(
+            model.get_value(&attribute_name!(update.mode)),
+            Some(&json!("manual"))
+        );
+
+        let model = parse_for_target(content, Some(ConfigurationTarget::User));
+        assert!(model.diagnostics().is_empty());
+
+        let model = parse_for_target(content, Some(ConfigurationTarget::Workspace));
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.fontSize)),
+            Some(&json!(14))
+        );
+        assert_eq!(model.get_value(&attribute_name!(update.mode)), None);
+        assert_eq!(model.diagnostics().len(), 1);
+        assert_eq!(
+            model.diagnostics()[0].message,
+            "Setting `update.mode` has Application scope and can't be set in workspace settings"
+        );
+    }
+
+    #[test]
+    fn test_parse_for_target_with_property_scopes() {
+        let content = r#"{
+    "editor.formatOnSave": true,
+    "editor.gpuAcceleration": "off"
+}"#;
+
+        let model = parse_for_target(content, Some(ConfigurationTarget::WorkspaceFolder));
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.formatOnSave)),
+            Some(&json!(true))
+        );
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.gpuAcceleration)),
+            None
+        );
+        assert_eq!(model.diagnostics().len(), 1);
+        assert_eq!(
+            model.diagnostics()[0].message,
+            "Setting `editor.gpuAcceleration` has Machine scope and can't be set in workspace folder settings"
+        );
+
+        let model = parse_for_target(content, Some(ConfigurationTarget::Workspace));
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.formatOnSave)),
+            Some(&json!(true))
+        );
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.gpuAcceleration)),
+            None
+        );
+        assert_eq!(
+            model.diagnostics()[0].message,
+            "Setting `editor.gpuAcceleration` has Machine scope and can't be set in workspace settings"
+        );
+    }
+
+    #[test]
+    fn test_parse_migrates_deprecated_settings() {
+        let model = parse(
+            r#"{
+    "editor.size": 14,
+    "editor.tabWidth": "2"
+}"#,
+        );
+
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.fontSize)),
+            Some(&json!(14))
+        );
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.tabSize)),
+            Some(&json!(2))
+        );
+        assert_eq!(model.get_value(&attribute_name!(editor.size)), None);
+        assert_eq!(model.migrated_settings().len(), 2);
+        assert_eq!(model.migrated_settings()[0].key, "editor.size");
+        assert_eq!(model.migrated_settings()[0].new_key, "editor.fontSize");
+        assert!(model
+            .diagnostics()
+            .iter()
+            .all(|diagnostic| diagnostic.severity == DiagnosticSeverity::Info));
+
+        let model = parse(
+            r#"{
+    "editor.size": 14,
+    "editor.fontSize": 16,
+    "editor.tabWidth": "wide"
+}"#,
+        );
+
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.fontSize)),
+            Some(&json!(16))
+        );
+        assert_eq!(model.get_value(&attribute_name!(editor.tabSize)), None);
+        assert!(model.migrated_settings().is_empty());
+        assert_eq!(model.diagnostics().len(), 2);
+        assert_eq!(
+            model.diagnostics()[0].message,
+            "Setting `editor.size` is deprecated and ignored because `editor.fontSize` is set"
+        );
+        assert_eq!(
+            model.diagnostics()[1].message,
+            "The value of the deprecated setting `editor.tabWidth` can't be migrated to `editor.tabSize`"
+        );
+    }
+
+    #[test]
+    fn test_parse_does_not_record_rejected_migrations() {
+        let model = parse_for_target(
+            r#"{ "update.channel": "manual" }"#,
+            Some(ConfigurationTarget::Workspace),
+        );
+
+        assert_eq!(model.get_value(&attribute_name!(update.mode)), None);
+        assert!(model.migrated_settings().is_empty());
+        assert_eq!(model.diagnostics().len(), 1);
+        assert_eq!(
+            model.diagnostics()[0].message,
+            "Setting `update.mode` has Application scope and can't be set in workspace settings"
+        );
+
+        let model = parse_for_target(
+            r#"{ "update.channel": "manual" }"#,
+            Some(ConfigurationTarget::User),
+        );
+        assert_eq!(
+            model.get_value(&attribute_name!(update.mode)),
+            Some(&json!("manual"))
+        );
+        assert_eq!(model.migrated_settings().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_in_restricted_mode() {
+        let content = r#"{
+    "editor.fontSize": 14,
+    "editor.formatterPath": "./node_modules/.bin/formatter"
+}"#;
+
+        let model = parse_with(content, Some(ConfigurationTarget::Workspace), false);
+        assert!(model.diagnostics().is_empty());
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.formatterPath)),
+            Some(&json!("./node_modules/.bin/formatter"))
+        );
+
+        let model = parse_with(content, Some(ConfigurationTarget::Workspace), true);
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.fontSize)),
+            Some(&json!(14))
+        );
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.formatterPath)),
+            None
+        );
+        assert_eq!(model.diagnostics().len(), 1);
+        assert_eq!(
+            model.diagnostics()[0].message,
+            "Setting `editor.formatterPath` is ignored because the workspace is not trusted"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+/// Operating system the default values of the settings are resolved for,
+/// see `ConfigurationPropertySchema::platform_defaults`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigurationPlatform {
+    Linux,
+    MacOS,
+    Windows,
+}
+
+impl ConfigurationPlatform {
+    /// Returns the platform the application was built for.
+    pub fn current() -> Self {
+        if cfg!(target_os = "macos") {
+            Self::MacOS
+        } else if cfg!(target_os = "windows") {
+            Self::Windows
+        } else {
+            Self::Linux
+        }
+    }
+
+    /// Resolves the platform from the OS name reported by the system, e.g. `NativePlatformInfo::os`.
+    /// Linux systems report the name of the distribution, e.g. `Ubuntu`, so any name that is neither
+    /// Windows nor macOS is read as Linux. An unknown name falls back to `current`.
+    pub fn from_os_name(os: &str) -> Self {
+        let os = os.trim().to_lowercase();
+
+        if os.is_empty() || os == "unknown" {
+            Self::current()
+        } else if os.contains("windows") {
+            Self::Windows
+        } else if os.contains("darwin") || os.contains("mac") {
+            Self::MacOS
+        } else {
+            Self::Linux
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigurationPlatform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Linux => write!(f, "Linux"),
+            Self::MacOS => write!(f, "macOS"),
+            Self::Windows => write!(f, "Windows"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigurationPlatform;
+
+    #[test]
+    fn test_from_os_name() {
+        assert_eq!(
+            ConfigurationPlatform::from_os_name("Darwin"),
+            ConfigurationPlatform::MacOS
+        );
+        assert_eq!(
+            ConfigurationPlatform::from_os_name("Windows"),
+            ConfigurationPlatform::Windows
+        );
+        assert_eq!(
+            ConfigurationPlatform::from_os_name("Fedora Linux"),
+            ConfigurationPlatform::Linux
+        );
+        assert_eq!(
+            ConfigurationPlatform::from_os_name("unknown"),
+            ConfigurationPlatform::current()
+        );
+    }
+}
@@ -6,15 +6,17 @@ use platform_core::context_v2::{atom::Atom, Context};
 
 use super::{
     configuration_default::DefaultConfiguration,
+    configuration_diagnostic::ConfigurationDiagnostic,
+    configuration_key::ConfigurationKey,
     configuration_model::ConfigurationModel,
-    configuration_registry::{ConfigurationRegistry, PropertyPolicy},
+    configuration_registry::{ConfigurationNodeType, ConfigurationRegistry, PropertyPolicy},
     policy::{PolicyDefinitionType, PolicyService},
 };
 
 pub struct ConfigurationPolicy {
     model: ArcSwap<ConfigurationModel>,
     registry: Atom<ConfigurationRegistry>,
-    policy_service: ConfigurationPolicyService,
+    policy_service: ArcSwap<ConfigurationPolicyService>,
 }
 
 impl<'a> ConfigurationPolicy {
@@ -25,20 +27,45 @@ impl<'a> ConfigurationPolicy {
         Self {
             model: ArcSwap::new(Arc::new(ConfigurationModel::empty())),
             registry,
-            policy_service,
+            policy_service: ArcSwap::new(Arc::new(policy_service)),
         }
     }
 
-    pub fn initialize(&mut self, ctx: &mut Context, default_configuration: &DefaultConfiguration) {
+    pub fn initialize(&self, ctx: &mut Context, default_configuration: &DefaultConfiguration) {
         let default_configuration_model = default_configuration.get_configuration_model().unwrap(); // TODO: handle panic (should never happen)
+        let policy_service = self.policy_service.load_full();
 
         let mut configuration_model = ConfigurationModel::empty();
+        configuration_model
+            .diagnostics
+            .extend(policy_service.diagnostics.iter().cloned());
 
-        for (property_key, property_policy) in
+        for (property_key, (property_policy, property_type)) in
             self.find_all_model_policies(ctx, default_configuration_model)
         {
-            if let Some(property_value) = self.policy_service.get_value(&property_policy.name) {
-                configuration_model.set_value(property_key, property_value.clone());
+            if let Some(property_value) = policy_service.get_value(&property_policy.name) {
+                if let Err(message) = Self::check_policy_value(
+                    &policy_service,
+                    &property_policy,
+                    property_type.as_ref(),
+                    property_value,
+                ) {
+                    configuration_model.diagnostics.push(
+                        ConfigurationDiagnostic::error(format!(
+                            "Policy `{}` can't be applied to setting `{}`: {}",
+                            property_policy.name, property_key, message
+                        ))
+                        .with_key(property_key.to_string()),
+                    );
+
+                    continue;
+                }
+
+                configuration_model.set_policy_value(
+                    property_key,
+                    property_value.clone(),
+                    property_policy.name,
+                );
             } else {
                 // TODO: handle when value is None
             }
@@ -51,11 +78,52 @@ impl<'a> ConfigurationPolicy {
         self.model.load_full()
     }
 
+    /// Replaces the policies, e.g. after the policy file has changed, and rebuilds the model.
+    pub fn update_policy_service(
+        &self,
+        ctx: &mut Context,
+        default_configuration: &DefaultConfiguration,
+        policy_service: ConfigurationPolicyService,
+    ) -> Arc<ConfigurationModel> {
+        self.policy_service.store(Arc::new(policy_service));
+        self.initialize(ctx, default_configuration);
+
+        self.get_model()
+    }
+
+    /// Checks the policy value against both the policy definition and the type of the setting,
+    /// so that a misconfigured policy can never lock a setting to a value of the wrong type.
+    fn check_policy_value(
+        policy_service: &ConfigurationPolicyService,
+        property_policy: &PropertyPolicy,
+        property_type: Option<&ConfigurationNodeType>,
+        value: &serde_json::Value,
+    ) -> Result<(), String> {
+        let definition = policy_service
+            .definitions
+            .get(&property_policy.name)
+            .ok_or_else(|| String::from("the policy is not defined"))?;
+
+        // `ConfigurationNodeType::Null` is the type of settings that don't declare one.
+        if let Some(property_type) =
+            property_type.filter(|typ| **typ != ConfigurationNodeType::Null)
+        {
+            if !definition.is_compatible_with(property_type) {
+                return Err(format!(
+                    "the policy is defined as {}, but the setting is of type {:?}",
+                    definition, property_type
+                ));
+            }
+        }
+
+        definition.validate(value).map_err(|err| err.to_string())
+    }
+
     fn find_all_model_policies(
         &self,
         ctx: &mut Context,
         model: Arc<ConfigurationModel>,
-    ) -> HashMap<String, PropertyPolicy> {
+    ) -> HashMap<ConfigurationKey, (PropertyPolicy, Option<ConfigurationNodeType>)> {
         let configuration_properties = self.registry.read(ctx).properties();
         let mut property_policies = HashMap::new();
 
@@ -69,7 +137,10 @@ impl<'a> ConfigurationPolicy {
             if let Some(property_policy) = &property.schema.policy {
                 // TODO: check for uniqueness and warning if the key already exists
 
-                property_policies.insert(key.clone(), property_policy.clone());
+                property_policies.insert(
+                    key.clone(),
+                    (property_policy.clone(), property.schema.typ.clone()),
+                );
             }
         }
 
@@ -80,6 +151,18 @@ impl<'a> ConfigurationPolicy {
 pub struct ConfigurationPolicyService {
     pub definitions: HashMap<String, PolicyDefinitionType>,
     pub policies: HashMap<String, serde_json::Value>,
+    /// Problems found while reading the policies, e.g. values that don't match their definition.
+    pub diagnostics: Vec<ConfigurationDiagnostic>,
+}
+
+impl ConfigurationPolicyService {
+    pub fn new(definitions: HashMap<String, PolicyDefinitionType>) -> Self {
+        Self {
+            definitions,
+            policies: HashMap::new(),
+            diagnostics: Vec::new(),
+        }
+    }
 }
 
 impl PolicyService for ConfigurationPolicyService {
@@ -87,3 +170,113 @@ impl PolicyService for ConfigurationPolicyService {
         self.policies.get(&name.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attribute_name,
+        configuration_registry::{
+            test_utils::registry_with, ConfigurationPropertySchema, PropertyMap,
+        },
+        property_key,
+    };
+    use platform_core::context_v2::ContextCell;
+    use platform_core::platform::cross::client::CrossPlatformClient;
+    use serde_json::json;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_policy_model_contains_policy_values() {
+        let ctx_cell = ContextCell::new(Rc::new(CrossPlatformClient::new()));
+        let ctx: &mut Context = &mut ctx_cell.borrow_mut();
+
+        let mut properties = PropertyMap::new();
+        properties.insert(
+            property_key!(editor.lineHeight),
+            ConfigurationPropertySchema {
+                default: Some(json!(20)),
+                policy: Some(PropertyPolicy {
+                    name: "editorLineHeightPolicy".to_string(),
+                }),
+                ..Default::default()
+            },
+        );
+        properties.insert(
+            property_key!(editor.fontSize),
+            ConfigurationPropertySchema {
+                default: Some(json!(12)),
+                ..Default::default()
+            },
+        );
+        let registry = registry_with(ctx, "editor", properties);
+
+        let default_configuration = DefaultConfiguration::new(registry.clone());
+        default_configuration.initialize(ctx);
+
+        let mut policy_service = ConfigurationPolicyService::new(HashMap::from_iter([(
+            "editorLineHeightPolicy".to_string(),
+            PolicyDefinitionType::Number,
+        )]));
+        policy_service
+            .policies
+            .insert("editorLineHeightPolicy".to_string(), json!(45));
+
+        let configuration_policy = ConfigurationPolicy::new(registry, policy_service);
+        configuration_policy.initialize(ctx, &default_configuration);
+
+        let model = configuration_policy.get_model();
+        assert_eq!(
+            model.get_value(&attribute_name!(editor.lineHeight)),
+            Some(&json!(45))
+        );
+        assert_eq!(
+            model.get_policy_name(&attribute_name!(editor.lineHeight)),
+            Some("editorLineHeightPolicy")
+        );
+        assert_eq!(model.get_value(&attribute_name!(editor.fontSize)), None);
+    }
+
+    #[test]
+    fn test_policy_incompatible_with_property_type() {
+        let ctx_cell = ContextCell::new(Rc::new(CrossPlatformClient::new()));
+        let ctx: &mut Context = &mut ctx_cell.borrow_mut();
+
+        let mut properties = PropertyMap::new();
+        properties.insert(
+            property_key!(update.enabled),
+            ConfigurationPropertySchema {
+                typ: Some(ConfigurationNodeType::Bool),
+                default: Some(json!(true)),
+                policy: Some(PropertyPolicy {
+                    name: "updatePolicy".to_string(),
+                }),
+                ..Default::default()
+            },
+        );
+        let registry = registry_with(ctx, "update", properties);
+
+        let default_configuration = DefaultConfiguration::new(registry.clone());
+        default_configuration.initialize(ctx);
+
+        let mut policy_service = ConfigurationPolicyService::new(HashMap::from_iter([(
+            "updatePolicy".to_string(),
+            PolicyDefinitionType::String,
+        )]));
+        policy_service
+            .policies
+            .insert("updatePolicy".to_string(), json!("none"));
+
+        let configuration_policy = ConfigurationPolicy::new(registry, policy_service);
+        configuration_policy.initialize(ctx, &default_configuration);
+
+        let model = configuration_policy.get_model();
+        assert_eq!(model.get_value(&attribute_name!(update.enabled)), None);
+        assert_eq!(model.diagnostics().len(), 1);
+        assert_eq!(
+            model.diagnostics()[0].message,
+            "Policy `updatePolicy` can't be applied to setting `update.enabled`: \
+             the policy is defined as a string, but the setting is of type Bool"
+        );
+    }
+}
@@ -2,16 +2,22 @@ use std::{any::Any, sync::Arc};
 
 use hashbrown::{HashMap, HashSet};
 use lazy_regex::Regex as LazyRegex;
-use platform_core::base::collection::extend::MaybeExtend;
 use platform_core::context_v2::node::AnyNodeValue;
 // use platform_core::global::Global;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
 
+use crate::{
+    configuration_diagnostic::ConfigurationDiagnostic, configuration_key::ConfigurationKey,
+    configuration_migration::ConfigurationMigration, configuration_platform::ConfigurationPlatform,
+};
+
 type Regex = LazyRegex;
 
 /// Enumeration representing the scope of a configuration setting.
 /// This enum defines the different levels at which a configuration setting can be applied.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ConfigurationScope {
     /// Application-specific configuration that applies globally across the entire platform.
     Application,
@@ -30,10 +36,12 @@ impl Default for ConfigurationScope {
 }
 
 /// Enumeration representing the type of a configuration setting.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ConfigurationNodeType {
     Null,
     String,
+    #[serde(rename = "boolean", alias = "bool")]
     Bool,
     Number,
     Array,
@@ -57,143 +65,75 @@ impl ConfigurationNodeType {
             ConfigurationNodeType::Object => Value::Object(serde_json::Map::new()),
         }
     }
-}
-
-/// A struct representing a configuration property key with optional overrides.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct PropertyKey {
-    /// A set of overrides for specific contexts.
-    pub override_for: Option<HashSet<String>>,
-    /// The identifier string for the key, potentially with sub-identifiers.
-    pub ident: String,
-}
-
-impl PropertyKey {
-    pub fn distinct(&self) -> Vec<String> {
-        self.override_for.as_ref().map_or_else(
-            || vec![self.ident.clone()],
-            |overrides| {
-                overrides
-                    .iter()
-                    .map(|override_ident| format!("[{}].{}", override_ident, self.ident))
-                    .collect()
-            },
-        )
-    }
 
-    // OPTIMIZE: rewrite parsing using regular expressions
-    /// Parses a string to create a `Key` instance using regular expressions.
-    /// The expected format is `[override1][override2].ident.subident`.
-    pub fn parse(s: &str) -> Result<Self, String> {
-        let re = regex!(r"(\[([^\]]+)\])|([^.]+)");
-        let mut overrides = HashSet::new();
-        let mut ident_parts = Vec::new();
-        let remaining = s;
-
-        // Check for mismatched brackets
-        let open_brackets = remaining.matches('[').count();
-        let close_brackets = remaining.matches(']').count();
-        if open_brackets != close_brackets {
-            return Err("Mismatched brackets in override section".to_string());
+    /// Checks whether the value has this type.
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            ConfigurationNodeType::Null => value.is_null(),
+            ConfigurationNodeType::String => value.is_string(),
+            ConfigurationNodeType::Bool => value.is_boolean(),
+            ConfigurationNodeType::Number => value.is_number(),
+            ConfigurationNodeType::Array => value.is_array(),
+            ConfigurationNodeType::Object => value.is_object(),
         }
-
-        for cap in re.captures_iter(remaining) {
-            if let Some(override_) = cap.get(2) {
-                overrides.insert(override_.as_str().to_string());
-            } else if let Some(ident_part) = cap.get(3) {
-                ident_parts.push(ident_part.as_str());
-            }
-        }
-
-        if ident_parts.is_empty() {
-            return Err("Missing identifier".to_string());
-        }
-
-        let ident = ident_parts.join(".");
-
-        Ok(PropertyKey {
-            override_for: if overrides.is_empty() {
-                None
-            } else {
-                Some(overrides)
-            },
-            ident,
-        })
     }
 }
 
-/// A macro to create a `Key` struct with optional overrides and sub-identifiers.
+/// A macro to create the keys of a property, a key for every override identifier,
+/// or the key of the property itself if it has none.
 ///
 /// # Examples
 ///
 /// Basic usage with no overrides:
 ///
 /// ```rust
-/// let key = property_key!(editor.fontSize);
-/// assert_eq!(key.override_for.is_empty(), true);
-/// assert_eq!(key.ident, "editor.fontSize");
+/// # use platform_configuration::{configuration_key::ConfigurationKey, property_key};
+/// let keys = property_key!(editor.fontSize);
+/// assert_eq!(keys, vec![ConfigurationKey::new("editor.fontSize")]);
 /// ```
 ///
 /// Usage with a single override:
 ///
 /// ```rust
-/// let key = property_key!([rust].editor.fontSize);
-/// assert!(key.override_for.contains("rust"));
-/// assert_eq!(key.ident, "editor.fontSize");
+/// # use platform_configuration::{configuration_key::ConfigurationKey, property_key};
+/// let keys = property_key!([rust].editor.fontSize);
+/// assert_eq!(keys, vec![ConfigurationKey::with_override("rust", "editor.fontSize")]);
 /// ```
 ///
 /// Usage with multiple overrides:
 ///
 /// ```rust
-/// let key = property_key!([typescript][javascript].editor.fontSize);
-/// assert!(key.override_for.contains("typescript"));
-/// assert!(key.override_for.contains("javascript"));
-/// assert_eq!(key.ident, "editor.fontSize");
+/// # use platform_configuration::{configuration_key::ConfigurationKey, property_key};
+/// let keys = property_key!([typescript][javascript].editor.fontSize);
+/// assert_eq!(
+///     keys,
+///     vec![
+///         ConfigurationKey::with_override("typescript", "editor.fontSize"),
+///         ConfigurationKey::with_override("javascript", "editor.fontSize"),
+///     ]
+/// );
 /// ```
 #[macro_export]
 macro_rules! property_key {
-    // Collect overrides and construct the Key with sub-identifiers
-    (@collect_overrides [$($override:ident)+] $ident:ident $(. $subident:ident)*) => {{
-        use hashbrown::HashSet;
-
-
-        let mut overrides = HashSet::new();
-        $(
-            overrides.insert(stringify!($override).to_string());
-        )+
-        let ident = concat!(stringify!($ident), $(concat!(".", stringify!($subident))),*).to_string();
-        $crate::configuration_registry::PropertyKey {
-            override_for: if overrides.is_empty() { None } else { Some(overrides) },
-            ident,
-        }
+    // Handle one or more overrides
+    ($([$override:ident])+ . $ident:ident $(. $subident:ident)*) => {{
+        let name = concat!(stringify!($ident), $(concat!(".", stringify!($subident))),*);
+        vec![$(
+            $crate::configuration_key::ConfigurationKey::with_override(stringify!($override), name)
+        ),+]
     }};
 
-    // Handle a single override
-    ([$override:ident] . $($tail:tt)*) => {
-        property_key!(@collect_overrides [$override] $($tail)*)
-    };
-
-    // Handle multiple overrides
-    ([$first:ident] $([$rest:ident])+ . $($tail:tt)*) => {
-        property_key!(@collect_overrides [$first $($rest)+] $($tail)*)
-    };
-
     // Handle the case without overrides and with sub-identifiers
-    ($ident:ident $(. $subident:ident)*) => {{
-        use hashbrown::HashSet;
-
-        let overrides: HashSet<String> = HashSet::new();
-        let ident = concat!(stringify!($ident), $(concat!(".", stringify!($subident))),*).to_string();
-        $crate::configuration_registry::PropertyKey {
-            override_for: if overrides.is_empty() { None } else { Some(overrides) },
-            ident,
-        }
-    }};
+    ($ident:ident $(. $subident:ident)*) => {
+        vec![$crate::configuration_key::ConfigurationKey::new(
+            concat!(stringify!($ident), $(concat!(".", stringify!($subident))),*),
+        )]
+    };
 }
 
 #[derive(Debug, Clone)]
 pub struct PropertyMap {
-    table: HashMap<String, ConfigurationPropertySchema>,
+    table: HashMap<ConfigurationKey, ConfigurationPropertySchema>,
     overrides: HashSet<String>,
 }
 
@@ -207,8 +147,8 @@ impl Default for PropertyMap {
 }
 
 impl IntoIterator for PropertyMap {
-    type Item = (String, ConfigurationPropertySchema);
-    type IntoIter = hashbrown::hash_map::IntoIter<String, ConfigurationPropertySchema>;
+    type Item = (ConfigurationKey, ConfigurationPropertySchema);
+    type IntoIter = hashbrown::hash_map::IntoIter<ConfigurationKey, ConfigurationPropertySchema>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.table.into_iter()
@@ -216,8 +156,8 @@ impl IntoIterator for PropertyMap {
 }
 
 impl<'a> IntoIterator for &'a PropertyMap {
-    type Item = (&'a String, &'a ConfigurationPropertySchema);
-    type IntoIter = hashbrown::hash_map::Iter<'a, String, ConfigurationPropertySchema>;
+    type Item = (&'a ConfigurationKey, &'a ConfigurationPropertySchema);
+    type IntoIter = hashbrown::hash_map::Iter<'a, ConfigurationKey, ConfigurationPropertySchema>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.table.iter()
@@ -241,40 +181,110 @@ impl PropertyMap {
         self.table.extend(item.table);
     }
 
-    pub fn insert(&mut self, key: PropertyKey, value: ConfigurationPropertySchema) {
-        for k in key.distinct() {
-            self.table.insert(k, value.clone());
+    /// Registers the schema under every key, see `ConfigurationKey::parse` for the keys
+    /// of a property with several override identifiers.
+    pub fn insert(
+        &mut self,
+        keys: impl IntoIterator<Item = ConfigurationKey>,
+        value: ConfigurationPropertySchema,
+    ) {
+        for key in keys {
+            if let Some(override_ident) = &key.override_ident {
+                self.overrides.insert(override_ident.clone());
+            }
+
+            self.table.insert(key, value.clone());
         }
+    }
 
-        self.overrides.maybe_extend(key.override_for);
+    pub fn retain(
+        &mut self,
+        f: impl FnMut(&ConfigurationKey, &mut ConfigurationPropertySchema) -> bool,
+    ) {
+        self.table.retain(f);
     }
 }
 
-#[derive(Debug, Clone)]
+/// Written as an object mapping the keys of the properties, e.g. `[rust].editor.tabSize`, to their schemas.
+impl Serialize for PropertyMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.table
+                .iter()
+                .map(|(key, schema)| (key.to_string(), schema)),
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for PropertyMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let properties = HashMap::<String, ConfigurationPropertySchema>::deserialize(deserializer)?;
+
+        let mut result = PropertyMap::new();
+        for (key, schema) in properties {
+            let keys = ConfigurationKey::parse(&key).map_err(|err| {
+                de::Error::custom(format!("invalid property key `{}`: {}", key, err))
+            })?;
+            result.insert(keys, schema);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Reads and writes the pattern of a string property as the source of the regular expression.
+mod string_pattern {
+    use super::Regex;
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        pattern: &Option<Regex>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        pattern.as_ref().map(Regex::as_str).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Regex>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|pattern| Regex::new(&pattern).map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum StringPresentationFormatType {
     Multiline,
     Singleline,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PropertyPolicy {
     pub name: String,
 }
 
 /// Struct representing a schema for a configuration property.
 /// This struct defines the metadata and constraints for a configuration setting.
-#[derive(Debug, Clone)]
+/// Missing fields of a declarative contribution take the values of `Default`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct ConfigurationPropertySchema {
     /// Unique identifier for the property.
     pub id: Option<String>,
     /// The scope of the configuration property, indicating the level at which it applies.
+    /// A property without a scope takes the scope of the node that registers it.
     pub scope: Option<ConfigurationScope>,
     /// The type of the configuration property, specifying the kind of value it holds.
+    #[serde(rename = "type")]
     pub typ: Option<ConfigurationNodeType>,
     /// The order in which the configuration property appears in the settings UI.
     pub order: Option<usize>,
     /// The default value of the configuration property, if any.
     pub default: Option<Value>,
+    /// Default values that replace `default` on some platforms, e.g. a different font on macOS.
+    pub platform_defaults: Option<HashMap<ConfigurationPlatform, Value>>,
     /// A description of the configuration property, providing context and usage information.
     pub description: Option<String>,
     /// Indicates if the configuration property is protected from contributions by extensions.
@@ -288,6 +298,9 @@ pub struct ConfigurationPropertySchema {
     pub included: bool,
     /// Indicates that a property is deprecated.
     pub deprecated: bool,
+    /// Indicates that the value is sensitive, e.g. an access token,
+    /// so it is left out when the settings are exported.
+    pub secret: bool,
     /// Tags associated with the property:
     /// - For filtering
     /// - Use `experimental` to mark property as experimental.
@@ -312,6 +325,7 @@ pub struct ConfigurationPropertySchema {
     pub array_unique_items: Option<bool>,
 
     /// Pattern that the string must match.
+    #[serde(with = "string_pattern")]
     pub string_pattern: Option<Regex>,
     /// Minimum length of the string.
     pub string_min_length: Option<usize>,
@@ -326,6 +340,7 @@ pub struct ConfigurationPropertySchema {
     pub number_max_value: Option<isize>,
 
     /// Allowed values for a property.
+    #[serde(rename = "enum")]
     pub enum_items: Option<Value>,
     /// Labels for enum items
     pub enum_item_labels: Option<Vec<String>>,
@@ -338,15 +353,17 @@ impl Default for ConfigurationPropertySchema {
 
         Self {
             id: None,
-            scope: Some(ConfigurationScope::Window),
+            scope: None,
             typ: Some(ConfigurationNodeType::Null),
             order: None,
             default: Some(default_default_value),
+            platform_defaults: None,
             description: None,
             protected_from_contribution: false,
             allow_for_only_restricted_source: false,
             included: true,
             deprecated: false,
+            secret: false,
             tags: None,
             policy: None,
             max_properties: None,
@@ -367,14 +384,28 @@ impl Default for ConfigurationPropertySchema {
     }
 }
 
-#[derive(Debug, Clone)]
+impl ConfigurationPropertySchema {
+    /// Returns the default value on the platform, falling back to `default`
+    /// if the property has no default of its own for the platform.
+    pub fn default_for(&self, platform: ConfigurationPlatform) -> Option<&Value> {
+        self.platform_defaults
+            .as_ref()
+            .and_then(|defaults| defaults.get(&platform))
+            .or(self.default.as_ref())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ConfigurationSource {
     pub id: String,
     pub display_name: Option<String>,
 }
 
 /// Struct representing a configuration node.
-#[derive(Debug, Clone)]
+/// Nodes can also be contributed by declarative manifests, see `configuration_contribution`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ConfigurationNode {
     /// The ID of the configuration node.
     pub id: String,
@@ -383,6 +414,7 @@ pub struct ConfigurationNode {
     /// The order in which the configuration node appears.
     pub order: Option<usize>,
     /// The type of the configuration node.
+    #[serde(rename = "type")]
     pub typ: Option<ConfigurationNodeType>,
     /// The title of the configuration node.
     pub title: Option<String>,
@@ -471,15 +503,17 @@ impl ConfigurationSchemaStorage {
         }
     }
 
-    fn update_schema(&mut self, key: &str, property: &ConfigurationPropertySchema) {
+    /// Stores the schema by the scope of the property, or by the scope of its node if it has none.
+    fn update_schema(
+        &mut self,
+        key: &ConfigurationKey,
+        property: &ConfigurationPropertySchema,
+        node_scope: &ConfigurationScope,
+    ) {
         self.all_settings_schema
             .insert(key.to_string(), property.clone());
 
-        match property
-            .scope
-            .as_ref()
-            .unwrap_or(&ConfigurationScope::Window)
-        {
+        match property.scope.as_ref().unwrap_or(node_scope) {
             ConfigurationScope::Application => {
                 self.platform_settings_schema
                     .insert(key.to_string(), property.clone());
@@ -506,7 +540,7 @@ pub struct ConfigurationRegistry {
     /// Map of configuration properties.
     /// This hashmap stores the properties of configurations, indexed by their keys.
     /// Each property includes metadata such as type, scope, default values, and descriptions.
-    properties: HashMap<String, RegisteredConfigurationPropertySchema>,
+    properties: HashMap<ConfigurationKey, RegisteredConfigurationPropertySchema>,
 
     /// List of configuration nodes contributed.
     /// This map contains all configuration nodes that have been registered to the registry.
@@ -526,7 +560,10 @@ pub struct ConfigurationRegistry {
     /// Map of excluded configuration properties.
     /// This hashmap stores properties that are explicitly excluded from the configuration registry.
     /// These properties are not included in the configuration schema and are not available for users to configure.
-    excluded_properties: HashMap<String, RegisteredConfigurationPropertySchema>,
+    excluded_properties: HashMap<ConfigurationKey, RegisteredConfigurationPropertySchema>,
+
+    /// Migrations of deprecated settings, indexed by the deprecated key.
+    migrations: HashMap<String, ConfigurationMigration>,
 }
 
 impl AnyNodeValue for ConfigurationRegistry {
@@ -547,14 +584,37 @@ impl<'a> ConfigurationRegistry {
             override_identifiers: HashSet::new(),
             schema_storage: ConfigurationSchemaStorage::empty(),
             excluded_properties: HashMap::new(),
+            migrations: HashMap::new(),
         }
     }
 
-    pub fn properties(&self) -> &HashMap<String, RegisteredConfigurationPropertySchema> {
+    pub fn properties(&self) -> &HashMap<ConfigurationKey, RegisteredConfigurationPropertySchema> {
         &self.properties
     }
 
-    pub fn excluded_properties(&self) -> &HashMap<String, RegisteredConfigurationPropertySchema> {
+    /// Returns the property registered for the setting, without override.
+    pub fn get_property(&self, name: &str) -> Option<&RegisteredConfigurationPropertySchema> {
+        self.properties.get(&ConfigurationKey::new(name))
+    }
+
+    /// Checks whether settings, or migrations of deprecated settings, are registered in the section,
+    /// e.g. `window` for `window.defaultWidth`.
+    pub fn has_section(&self, section: &str) -> bool {
+        let is_in_section = |key: &ConfigurationKey| {
+            !key.is_override()
+                && matches!(key.relative_name(section), Some(name) if !name.is_empty())
+        };
+
+        self.properties.keys().any(is_in_section)
+            || self
+                .migrations
+                .keys()
+                .any(|key| is_in_section(&ConfigurationKey::new(key.as_str())))
+    }
+
+    pub fn excluded_properties(
+        &self,
+    ) -> &HashMap<ConfigurationKey, RegisteredConfigurationPropertySchema> {
         &self.excluded_properties
     }
 
@@ -562,7 +622,22 @@ impl<'a> ConfigurationRegistry {
         &self.override_identifiers
     }
 
+    pub fn get_migration(&self, key: &str) -> Option<&ConfigurationMigration> {
+        self.migrations.get(key)
+    }
+
+    /// Registers the migration of a deprecated setting. The values of the deprecated setting are read
+    /// as values of the new one from then on, a migration registered for the same key replaces the previous one.
+    pub fn register_migration(&mut self, migration: ConfigurationMigration) {
+        self.migrations.insert(migration.key.clone(), migration);
+    }
+
+    /// Registers the node, replacing the settings registered before with the same keys.
+    /// Settings that are protected from contribution are never replaced.
     pub fn register_configuration(&mut self, configuration: &'a ConfigurationNode) {
+        let configuration =
+            self.without_registered_properties(configuration, false, &mut Vec::new());
+
         self.contributors
             .insert(configuration.id.clone(), configuration.clone());
         self.register_json_configuration(&configuration);
@@ -572,6 +647,70 @@ impl<'a> ConfigurationRegistry {
         // TODO: Emit schema change events
     }
 
+    /// Registers a node contributed by a manifest, see `configuration_contribution`.
+    /// A contribution can only add settings: the settings already registered, either built-in
+    /// or contributed before, are left out and returned as diagnostics.
+    pub fn register_contribution(
+        &mut self,
+        configuration: &ConfigurationNode,
+    ) -> Vec<ConfigurationDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let configuration =
+            self.without_registered_properties(configuration, true, &mut diagnostics);
+        self.register_configuration(&configuration);
+
+        diagnostics
+    }
+
+    /// Returns the node without the properties that can't be registered again,
+    /// i.e. the protected ones, or every registered one if `is_contribution` is set.
+    fn without_registered_properties(
+        &self,
+        configuration: &ConfigurationNode,
+        is_contribution: bool,
+        diagnostics: &mut Vec<ConfigurationDiagnostic>,
+    ) -> ConfigurationNode {
+        let mut node = configuration.clone();
+
+        if let Some(properties) = node.properties.as_mut() {
+            properties.retain(|key, _| {
+                let Some(registered) = self
+                    .properties
+                    .get(key)
+                    .or_else(|| self.excluded_properties.get(key))
+                else {
+                    return true;
+                };
+
+                let message = if registered.is_protected_from_contribution() {
+                    format!(
+                        "Setting `{}` is protected from contribution and can't be registered by `{}`",
+                        key, configuration.id
+                    )
+                } else if is_contribution {
+                    format!(
+                        "Setting `{}` is already registered and can't be contributed by `{}`",
+                        key, configuration.id
+                    )
+                } else {
+                    return true;
+                };
+
+                diagnostics.push(ConfigurationDiagnostic::warning(message).with_key(key.to_string()));
+                false
+            });
+        }
+
+        if let Some(sub_nodes) = node.parent_of.as_mut() {
+            for sub_node in sub_nodes.iter_mut() {
+                *sub_node =
+                    self.without_registered_properties(sub_node, is_contribution, diagnostics);
+            }
+        }
+
+        node
+    }
+
     fn do_configuration_registration(
         &mut self,
         configuration: &ConfigurationNode,
@@ -598,11 +737,14 @@ impl<'a> ConfigurationRegistry {
 
             let mut property_schema = property.clone();
 
-            if node_properties.overrides.get(key).is_some() {
+            if key.is_override() {
                 // Assigning a specific scope is redundant since this property already implies a particular context.
                 property_schema.scope = None;
             } else {
-                property_schema.scope = Some(node_scope_or_default.clone());
+                property_schema.scope = property
+                    .scope
+                    .clone()
+                    .or(Some(node_scope_or_default.clone()));
                 property_schema.allow_for_only_restricted_source =
                     property.allow_for_only_restricted_source;
             }
@@ -636,10 +778,15 @@ impl<'a> ConfigurationRegistry {
     }
 
     fn register_json_configuration(&mut self, configuration: &ConfigurationNode) {
+        let node_scope = configuration
+            .scope
+            .as_ref()
+            .unwrap_or(&ConfigurationScope::Window);
+
         if let Some(properties) = &configuration.properties {
             for (key, property) in properties {
                 if property.included {
-                    self.schema_storage.update_schema(key, property);
+                    self.schema_storage.update_schema(key, property, node_scope);
                 }
             }
         }
@@ -673,131 +820,92 @@ mod tests {
 
     #[test]
     fn test_key_with_multiple_overrides_and_sub_identifiers() {
-        let key = property_key!([typescript][javascript].editor.fontSize);
-        let mut expected_overrides = HashSet::new();
-        expected_overrides.insert("typescript".to_string());
-        expected_overrides.insert("javascript".to_string());
-        let expected_key = PropertyKey {
-            override_for: Some(expected_overrides),
-            ident: "editor.fontSize".to_string(),
-        };
-        assert_eq!(key, expected_key);
+        assert_eq!(
+            property_key!([typescript][javascript].editor.fontSize),
+            vec![
+                ConfigurationKey::with_override("typescript", "editor.fontSize"),
+                ConfigurationKey::with_override("javascript", "editor.fontSize"),
+            ]
+        );
     }
 
     #[test]
     fn test_key_with_single_override_and_sub_identifiers() {
-        let key = property_key!([rust].editor.fontSize);
-        let mut expected_overrides = HashSet::new();
-        expected_overrides.insert("rust".to_string());
-        let expected_key = PropertyKey {
-            override_for: Some(expected_overrides),
-            ident: "editor.fontSize".to_string(),
-        };
-        assert_eq!(key, expected_key);
+        assert_eq!(
+            property_key!([rust].editor.fontSize),
+            vec![ConfigurationKey::with_override("rust", "editor.fontSize")]
+        );
     }
 
     #[test]
     fn test_key_with_multiple_sub_identifiers() {
-        let key = property_key!(editor.fontSize.lineHeight);
-        let expected_overrides = HashSet::new();
-        let expected_key = PropertyKey {
-            override_for: Some(expected_overrides),
-            ident: "editor.fontSize.lineHeight".to_string(),
-        };
-        assert_eq!(key, expected_key);
+        assert_eq!(
+            property_key!(editor.fontSize.lineHeight),
+            vec![ConfigurationKey::new("editor.fontSize.lineHeight")]
+        );
     }
 
     #[test]
     fn test_key_with_single_identifier() {
-        let key = property_key!(editor);
-        let expected_overrides = HashSet::new();
-        let expected_key = PropertyKey {
-            override_for: Some(expected_overrides),
-            ident: "editor".to_string(),
-        };
-        assert_eq!(key, expected_key);
+        assert_eq!(property_key!(editor), vec![ConfigurationKey::new("editor")]);
     }
 
     #[test]
-    fn test_parse_with_multiple_overrides_and_sub_identifiers() {
-        let s = "[typescript][javascript].editor.fontSize";
-        let key = PropertyKey::parse(s).unwrap();
-        let mut expected_overrides = HashSet::new();
-        expected_overrides.insert("typescript".to_string());
-        expected_overrides.insert("javascript".to_string());
-        let expected_key = PropertyKey {
-            override_for: Some(expected_overrides),
-            ident: "editor.fontSize".to_string(),
-        };
-        assert_eq!(key, expected_key);
-    }
-
-    #[test]
-    fn test_parse_with_single_override_and_sub_identifiers() {
-        let s = "[rust].editor.fontSize";
-        let key = PropertyKey::parse(s).unwrap();
-        let mut expected_overrides = HashSet::new();
-        expected_overrides.insert("rust".to_string());
-        let expected_key = PropertyKey {
-            override_for: Some(expected_overrides),
-            ident: "editor.fontSize".to_string(),
-        };
-        assert_eq!(key, expected_key);
+    fn test_insert_with_multiple_overrides() {
+        let mut properties = PropertyMap::new();
+        properties.insert(
+            ConfigurationKey::parse("[typescript][javascript].editor.tabSize").unwrap(),
+            ConfigurationPropertySchema::default(),
+        );
+
+        assert_eq!(properties.table.len(), 2);
+        assert!(properties
+            .table
+            .contains_key(&ConfigurationKey::with_override(
+                "javascript",
+                "editor.tabSize"
+            )));
+        assert_eq!(
+            properties.get_overrides(),
+            &HashSet::from(["typescript".to_string(), "javascript".to_string()])
+        );
     }
+}
 
-    #[test]
-    fn test_parse_with_multiple_sub_identifiers() {
-        let s = "editor.fontSize.lineHeight";
-        let key = PropertyKey::parse(s).unwrap();
-        let expected_key = PropertyKey {
-            override_for: None,
-            ident: "editor.fontSize.lineHeight".to_string(),
-        };
-        assert_eq!(key, expected_key);
-    }
+/// Fixtures shared by the tests of the configuration modules.
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use platform_core::context_v2::{atom::Atom, AnyContext, Context};
 
-    #[test]
-    fn test_parse_with_single_identifier() {
-        let s = "editor";
-        let key = PropertyKey::parse(s).unwrap();
-        let expected_key = PropertyKey {
-            override_for: None,
-            ident: "editor".to_string(),
-        };
-        assert_eq!(key, expected_key);
-    }
+    use super::{ConfigurationNode, ConfigurationRegistry, PropertyMap};
 
-    #[test]
-    fn test_parse_with_multiple_overrides_single_sub_identifier() {
-        let s = "[typescript][javascript].editor";
-        let key = PropertyKey::parse(s).unwrap();
-        let mut expected_overrides = HashSet::new();
-        expected_overrides.insert("typescript".to_string());
-        expected_overrides.insert("javascript".to_string());
-        let expected_key = PropertyKey {
-            override_for: Some(expected_overrides),
-            ident: "editor".to_string(),
-        };
-        assert_eq!(key, expected_key);
+    /// A node registering `properties`, with all the other fields unset.
+    pub(crate) fn node(id: &str, properties: PropertyMap) -> ConfigurationNode {
+        ConfigurationNode {
+            id: id.to_string(),
+            scope: None,
+            order: None,
+            typ: None,
+            title: None,
+            description: None,
+            properties: Some(properties),
+            parent_of: None,
+            source: None,
+        }
     }
 
-    #[test]
-    fn test_parse_with_single_override_single_identifier() {
-        let s = "[rust].editor";
-        let key = PropertyKey::parse(s).unwrap();
-        let mut expected_overrides = HashSet::new();
-        expected_overrides.insert("rust".to_string());
-        let expected_key = PropertyKey {
-            override_for: Some(expected_overrides),
-            ident: "editor".to_string(),
-        };
-        assert_eq!(key, expected_key);
-    }
+    /// Creates a registry with `properties` registered by a single node.
+    pub(crate) fn registry_with(
+        ctx: &mut Context,
+        id: &str,
+        properties: PropertyMap,
+    ) -> Atom<ConfigurationRegistry> {
+        let node = node(id, properties);
 
-    #[test]
-    fn test_parse_with_invalid_format() {
-        let s = "[rust.editor.fontSize";
-        let result = PropertyKey::parse(s);
-        assert!(result.is_err());
+        ctx.create_atom(|_| {
+            let mut registry = ConfigurationRegistry::new();
+            registry.register_configuration(&node);
+            registry
+        })
     }
 }
@@ -0,0 +1,347 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    configuration_model::Configuration,
+    configuration_registry::{ConfigurationRegistry, RegisteredConfigurationPropertySchema},
+};
+
+/// A filter written as `@name` in a settings search query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingsFilter {
+    /// `@modified`: settings with a value different from the default one.
+    Modified,
+    /// `@deprecated`: deprecated settings.
+    Deprecated,
+    /// `@tag:<tag>`: settings with the tag, e.g. `@tag:experimental`.
+    Tag(String),
+}
+
+/// A parsed settings search query, e.g. `font size @modified @tag:experimental`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SettingsQuery {
+    pub text: String,
+    pub filters: Vec<SettingsFilter>,
+}
+
+impl SettingsQuery {
+    /// Unknown filters are searched for as text.
+    pub fn parse(query: &str) -> Self {
+        let mut words = Vec::new();
+        let mut filters = Vec::new();
+
+        for word in query.split_whitespace() {
+            let filter = match word.strip_prefix('@') {
+                Some("modified") => Some(SettingsFilter::Modified),
+                Some("deprecated") => Some(SettingsFilter::Deprecated),
+                Some(filter) => filter
+                    .strip_prefix("tag:")
+                    .filter(|tag| !tag.is_empty())
+                    .map(|tag| SettingsFilter::Tag(tag.to_lowercase())),
+                None => None,
+            };
+
+            match filter {
+                Some(filter) => filters.push(filter),
+                None => words.push(word),
+            }
+        }
+
+        Self {
+            text: words.join(" "),
+            filters,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingSearchResult {
+    pub key: String,
+    /// Readable name of the setting, e.g. `Editor › Font Size` for `editor.fontSize`.
+    pub title: String,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub deprecated: bool,
+    pub enum_item_labels: Option<Vec<String>>,
+    pub value: Option<Value>,
+    pub default_value: Option<Value>,
+    pub is_modified: bool,
+    /// Higher scores are better matches, results are sorted by it.
+    pub score: i64,
+}
+
+/// Searches the registered settings, ranking the matches on the key above the ones on the
+/// title, tags and description. Settings with the same score are sorted by their `order`, then by key.
+pub fn search_settings(
+    registry: &ConfigurationRegistry,
+    configuration: &Configuration,
+    query: &SettingsQuery,
+) -> Vec<SettingSearchResult> {
+    let consolidated_model = configuration.get_consolidated_configuration();
+    let default_model = configuration.get_default_configuration();
+    let pattern = query.text.to_lowercase();
+
+    let mut results = registry
+        .properties()
+        .iter()
+        .filter_map(|(key, property)| {
+            let value = consolidated_model.get(key).cloned();
+            let default_value = default_model.get(key).cloned();
+            let is_modified = value != default_value;
+            let tags = property_tags(property);
+
+            let is_filtered_out = query.filters.iter().any(|filter| match filter {
+                SettingsFilter::Modified => !is_modified,
+                SettingsFilter::Deprecated => !property.schema.deprecated,
+                SettingsFilter::Tag(tag) => !tags.contains(tag),
+            });
+            if is_filtered_out {
+                return None;
+            }
+
+            let key = key.to_string();
+            let title = format_title(&key);
+            let score = if pattern.is_empty() {
+                0
+            } else {
+                match_score(&pattern, &key, &title, &tags, &property.schema.description)?
+            };
+
+            Some((
+                property.schema.order,
+                SettingSearchResult {
+                    key,
+                    title,
+                    description: property.schema.description.clone(),
+                    tags,
+                    deprecated: property.schema.deprecated,
+                    enum_item_labels: property.schema.enum_item_labels.clone(),
+                    value,
+                    default_value,
+                    is_modified,
+                    score,
+                },
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    results.sort_by(|(order_a, a), (order_b, b)| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| {
+                order_a
+                    .unwrap_or(usize::MAX)
+                    .cmp(&order_b.unwrap_or(usize::MAX))
+            })
+            .then_with(|| a.key.cmp(&b.key))
+    });
+
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn property_tags(property: &RegisteredConfigurationPropertySchema) -> Vec<String> {
+    property
+        .schema
+        .tags
+        .as_deref()
+        .unwrap_or_default()
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Formats `editor.fontSize` as `Editor › Font Size`.
+fn format_title(key: &str) -> String {
+    key.split('.')
+        .map(|segment| {
+            let mut title = String::new();
+            for (index, c) in segment.chars().enumerate() {
+                if index == 0 {
+                    title.extend(c.to_uppercase());
+                } else if c.is_uppercase() {
+                    title.push(' ');
+                    title.push(c);
+                } else {
+                    title.push(c);
+                }
+            }
+            title
+        })
+        .collect::<Vec<_>>()
+        .join(" › ")
+}
+
+fn match_score(
+    pattern: &str,
+    key: &str,
+    title: &str,
+    tags: &[String],
+    description: &Option<String>,
+) -> Option<i64> {
+    let key_score = fuzzy_score(pattern, key).map(|score| score * 4);
+    let title_score = fuzzy_score(pattern, title).map(|score| score * 3);
+    let tag_score = tags
+        .iter()
+        .filter_map(|tag| fuzzy_score(pattern, tag))
+        .max()
+        .map(|score| score * 2);
+    // Descriptions are long, so only a match of every word of the pattern counts.
+    let description_score = description.as_ref().and_then(|description| {
+        let description = description.to_lowercase();
+        pattern
+            .split_whitespace()
+            .all(|word| description.contains(word))
+            .then_some(pattern.len() as i64)
+    });
+
+    [key_score, title_score, tag_score, description_score]
+        .into_iter()
+        .flatten()
+        .max()
+}
+
+/// Scores the characters of the lowercase pattern appearing in order in the text.
+/// Consecutive characters and characters at the start of words score higher.
+/// Whitespace in the pattern is ignored, so `font size` matches `fontSize`.
+fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for pattern_char in pattern.chars().filter(|c| !c.is_whitespace()) {
+        let index = (position..text.len())
+            .find(|&index| text[index].to_lowercase().eq(pattern_char.to_lowercase()))?;
+
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == index) {
+            score += 5;
+        }
+
+        let is_word_start =
+            index == 0 || text[index].is_uppercase() || !text[index - 1].is_alphanumeric();
+        if is_word_start {
+            score += 3;
+        }
+
+        previous_match = Some(index);
+        position = index + 1;
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        configuration_default::DefaultConfiguration,
+        configuration_key::ConfigurationKey,
+        configuration_model::ConfigurationModel,
+        configuration_registry::{
+            test_utils::registry_with, ConfigurationPropertySchema, PropertyMap,
+        },
+        property_key,
+    };
+    use platform_core::context_v2::{Context, ContextCell};
+    use platform_core::platform::cross::client::CrossPlatformClient;
+    use serde_json::json;
+    use std::{rc::Rc, sync::Arc};
+
+    #[test]
+    fn test_parse_query() {
+        let query = SettingsQuery::parse("font @modified size @tag:Experimental @unknown");
+
+        assert_eq!(query.text, "font size @unknown");
+        assert_eq!(
+            query.filters,
+            vec![
+                SettingsFilter::Modified,
+                SettingsFilter::Tag("experimental".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_search_settings() {
+        let ctx_cell = ContextCell::new(Rc::new(CrossPlatformClient::new()));
+        let ctx: &mut Context = &mut ctx_cell.borrow_mut();
+
+        let mut properties = PropertyMap::new();
+        properties.insert(
+            property_key!(editor.fontSize),
+            ConfigurationPropertySchema {
+                default: Some(json!(12)),
+                description: Some("Controls the font size in pixels".to_string()),
+                ..Default::default()
+            },
+        );
+        properties.insert(
+            property_key!(editor.fontFamily),
+            ConfigurationPropertySchema {
+                default: Some(json!("monospace")),
+                tags: Some("experimental".to_string()),
+                ..Default::default()
+            },
+        );
+        properties.insert(
+            property_key!(editor.lineHeight),
+            ConfigurationPropertySchema {
+                default: Some(json!(20)),
+                description: Some("Controls the height of a line".to_string()),
+                deprecated: true,
+                ..Default::default()
+            },
+        );
+        let registry = registry_with(ctx, "editor", properties);
+
+        let default_configuration = DefaultConfiguration::new(registry.clone());
+        default_configuration.initialize(ctx);
+
+        let mut user_model = ConfigurationModel::empty();
+        user_model.set_value(
+            ConfigurationKey::new("editor.fontFamily"),
+            json!("Fira Code"),
+        );
+
+        let configuration = Configuration::new(
+            default_configuration.get_configuration_model().unwrap(),
+            Arc::new(ConfigurationModel::empty()),
+            Arc::new(user_model),
+            Arc::new(ConfigurationModel::empty()),
+            Arc::new(ConfigurationModel::empty()),
+        );
+        let registry = registry.read(ctx);
+
+        let results = search_settings(registry, &configuration, &SettingsQuery::parse("font size"));
+        assert_eq!(results[0].key, "editor.fontSize");
+        assert_eq!(results[0].title, "Editor › Font Size");
+        assert_eq!(results[0].value, Some(json!(12)));
+        assert!(results
+            .iter()
+            .all(|result| result.key != "editor.lineHeight"));
+
+        let results = search_settings(registry, &configuration, &SettingsQuery::parse("@modified"));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].key, "editor.fontFamily");
+        assert_eq!(results[0].value, Some(json!("Fira Code")));
+
+        let results = search_settings(
+            registry,
+            &configuration,
+            &SettingsQuery::parse("@tag:experimental"),
+        );
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].key, "editor.fontFamily");
+
+        let results = search_settings(
+            registry,
+            &configuration,
+            &SettingsQuery::parse("height @deprecated"),
+        );
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].key, "editor.lineHeight");
+    }
+}
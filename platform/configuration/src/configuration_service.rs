@@ -1,25 +1,3 @@
-use anyhow::{Context as AnyhowContext, Result};
-use lazy_regex::Lazy;
-use platform_fs::disk::file_system_service::AbstractDiskFileSystemService;
-use platform_utl::queue::{thread_backend::ThreadBackend, Processor, Queue};
-use serde_json::Value;
-use std::{
-    fs::OpenOptions,
-    io::{Read, Seek, SeekFrom, Write},
-    path::PathBuf,
-    sync::Arc,
-};
-
-use crate::{configuration_parser::ConfigurationParser, user_settings::UserSettings};
-
-use super::{
-    configuration_default::DefaultConfiguration,
-    configuration_model::{AttributeName, Configuration, ConfigurationModel},
-    configuration_policy::{ConfigurationPolicy, ConfigurationPolicyService},
-    configuration_registry::ConfigurationRegistry,
-    AbstractConfigurationService,
-};
-
 // pub struct ConfigurationService {
 //     default_configuration: DefaultConfiguration,
 //     user_configuration: UserSettings,
@@ -89,7 +67,7 @@ use super::{
 //         Ok(())
 //     }
 
-//     async fn do_update_value(&self, attribute_name: &AttributeName, value: &Value) -> Result<()> {
+//     async fn do_update_value(&self, attribute_name: &ConfigurationKey, value: &Value) -> Result<()> {
 //         let inspected_value = self.configuration.inspect(attribute_name);
 //         if inspected_value.get_policy_value(attribute_name).is_some() {
 //             return Err(anyhow!(
@@ -117,12 +95,12 @@ use super::{
 
 // #[async_trait]
 // impl<'a> AbstractConfigurationService for ConfigurationService {
-//     fn get_value(&self, attribute_name: AttributeName) -> Option<Value> {
+//     fn get_value(&self, attribute_name: ConfigurationKey) -> Option<Value> {
 //         self.configuration.get_value(&attribute_name)
 //     }
 
 //     /// NOTE: The function only works to update non-object values ​​at the root level
-//     async fn update_value(&self, attribute_name: AttributeName, value: &Value) -> Result<()> {
+//     async fn update_value(&self, attribute_name: ConfigurationKey, value: &Value) -> Result<()> {
 //         // TODO:
 //         // - Use pointer instead of key
 //         // - Check if the setting being changed is a USER level setting
@@ -131,71 +109,3 @@ use super::{
 //         Ok(self.reload_configuration().await?)
 //     }
 // }
-
-// #[derive(Debug)]
-// pub struct ConfigurationEditingService {
-//     edited_resource: PathBuf,
-//     queue: Queue<ThreadBackend, ConfigurationWriteJob>,
-// }
-
-// #[derive(Debug)]
-// struct ConfigurationWriteJob {
-//     key: String, // JSON Path
-//     value: Option<serde_json::Value>,
-//     resource: PathBuf,
-// }
-
-// #[derive(Debug)]
-// struct ConfigurationWriteJobProcessor;
-
-// #[async_trait]
-// impl Processor<ConfigurationWriteJob> for ConfigurationWriteJobProcessor {
-//     async fn process(&self, job: ConfigurationWriteJob) {
-//         // TODO:
-//         // - Implement error handling when the event module is implemented
-//         // - Implement update logic for overriding and nested objects
-
-//         if let Ok(mut file) = OpenOptions::new().read(true).write(true).open(job.resource) {
-//             let mut content = String::new();
-//             file.read_to_string(&mut content).expect("read file");
-
-//             let mut json: Value = serde_json::from_str(&content).expect("read content");
-
-//             if let Some(obj) = json.as_object_mut() {
-//                 if let Some(v) = job.value {
-//                     obj.insert(job.key, v);
-//                 } else {
-//                     obj.remove(&job.key);
-//                 }
-//             }
-
-//             file.seek(SeekFrom::Start(0)).expect("seek to start");
-//             file.set_len(0).expect("truncate file");
-
-//             let json_string = serde_json::to_string_pretty(&json).expect("write content");
-//             file.write_all(json_string.as_bytes()).expect("write");
-//         };
-//     }
-// }
-
-// impl ConfigurationEditingService {
-//     fn new(edited_resource: PathBuf) -> Self {
-//         Self {
-//             edited_resource,
-//             queue: Queue::new(
-//                 Lazy::new(|| ThreadBackend::new()),
-//                 ConfigurationWriteJobProcessor {},
-//             ),
-//         }
-//     }
-
-//     async fn write(&self, key: String, value: Option<serde_json::Value>) {
-//         self.queue
-//             .enqueue(ConfigurationWriteJob {
-//                 key,
-//                 value,
-//                 resource: self.edited_resource.clone(),
-//             })
-//             .await
-//     }
-// }
@@ -0,0 +1,1129 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{ser::PrettyFormatter, Serializer, Value};
+
+/// Enumeration representing the kind of a node in a JSONC document tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Object,
+    Array,
+    /// A `"key": value` pair inside an object.
+    /// The first child is the key string, the second child is the value.
+    Property,
+    String,
+    Number,
+    Boolean,
+    Null,
+}
+
+/// A node of a JSONC document tree.
+/// Offsets and lengths are byte positions in the original text.
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub kind: NodeKind,
+    pub offset: usize,
+    pub length: usize,
+    /// The value of a literal node (string, number, boolean or null).
+    pub value: Option<Value>,
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    pub fn end(&self) -> usize {
+        self.offset + self.length
+    }
+
+    /// Returns the key of a `NodeKind::Property` node.
+    pub fn property_key(&self) -> Option<&str> {
+        if self.kind != NodeKind::Property {
+            return None;
+        }
+
+        self.children
+            .first()
+            .and_then(|key| key.value.as_ref())
+            .and_then(|key| key.as_str())
+    }
+
+    /// Returns the value node of a `NodeKind::Property` node.
+    pub fn property_value(&self) -> Option<&Node> {
+        if self.kind != NodeKind::Property {
+            return None;
+        }
+
+        self.children.get(1)
+    }
+
+    /// Finds the property with the given key in an object node.
+    /// If the key is present several times, the last occurrence wins, as it does when reading values.
+    pub fn find_property(&self, key: &str) -> Option<&Node> {
+        if self.kind != NodeKind::Object {
+            return None;
+        }
+
+        self.children
+            .iter()
+            .rev()
+            .find(|property| property.property_key() == Some(key))
+    }
+
+    /// Converts the node into a `serde_json::Value`, dropping comments and formatting.
+    pub fn to_value(&self) -> Value {
+        match self.kind {
+            NodeKind::Object => {
+                let mut map = serde_json::Map::new();
+                for property in &self.children {
+                    if let (Some(key), Some(value)) =
+                        (property.property_key(), property.property_value())
+                    {
+                        map.insert(key.to_string(), value.to_value());
+                    }
+                }
+
+                Value::Object(map)
+            }
+            NodeKind::Array => Value::Array(self.children.iter().map(Node::to_value).collect()),
+            NodeKind::Property => self
+                .property_value()
+                .map_or(Value::Null, |value| value.to_value()),
+            _ => self.value.clone().unwrap_or(Value::Null),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    OpenBrace,
+    CloseBrace,
+    OpenBracket,
+    CloseBracket,
+    Comma,
+    Colon,
+    String,
+    Number,
+    True,
+    False,
+    Null,
+    Unknown,
+    Eof,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    kind: TokenKind,
+    offset: usize,
+    length: usize,
+}
+
+/// Scanner that splits a JSONC text into tokens, skipping whitespace,
+/// line comments (`// ...`) and block comments (`/* ... */`).
+struct Scanner<'a> {
+    text: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+    /// Offsets of block comments that are not closed before the end of the text.
+    unterminated_comments: Vec<usize>,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            bytes: text.as_bytes(),
+            position: 0,
+            unterminated_comments: Vec::new(),
+        }
+    }
+
+    fn skip_trivia(&mut self) {
+        while self.position < self.bytes.len() {
+            match self.bytes[self.position] {
+                b' ' | b'\t' | b'\r' | b'\n' => self.position += 1,
+                b'/' if self.bytes.get(self.position + 1) == Some(&b'/') => {
+                    while self.position < self.bytes.len() && self.bytes[self.position] != b'\n' {
+                        self.position += 1;
+                    }
+                }
+                b'/' if self.bytes.get(self.position + 1) == Some(&b'*') => {
+                    let start = self.position;
+                    self.position += 2;
+                    while self.position < self.bytes.len()
+                        && !(self.bytes[self.position] == b'*'
+                            && self.bytes.get(self.position + 1) == Some(&b'/'))
+                    {
+                        self.position += 1;
+                    }
+                    if self.position >= self.bytes.len() {
+                        self.unterminated_comments.push(start);
+                    }
+                    self.position = (self.position + 2).min(self.bytes.len());
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn scan(&mut self) -> Token {
+        self.skip_trivia();
+
+        let offset = self.position;
+        let Some(&byte) = self.bytes.get(offset) else {
+            return Token {
+                kind: TokenKind::Eof,
+                offset,
+                length: 0,
+            };
+        };
+
+        let kind = match byte {
+            b'{' => self.single(TokenKind::OpenBrace),
+            b'}' => self.single(TokenKind::CloseBrace),
+            b'[' => self.single(TokenKind::OpenBracket),
+            b']' => self.single(TokenKind::CloseBracket),
+            b',' => self.single(TokenKind::Comma),
+            b':' => self.single(TokenKind::Colon),
+            b'"' => self.scan_string(),
+            b'-' | b'0'..=b'9' => self.scan_number(),
+            _ => self.scan_word(),
+        };
+
+        Token {
+            kind,
+            offset,
+            length: self.position - offset,
+        }
+    }
+
+    fn single(&mut self, kind: TokenKind) -> TokenKind {
+        self.position += 1;
+        kind
+    }
+
+    fn scan_string(&mut self) -> TokenKind {
+        self.position += 1;
+        while let Some(&byte) = self.bytes.get(self.position) {
+            match byte {
+                b'"' => {
+                    self.position += 1;
+                    return TokenKind::String;
+                }
+                b'\\' => self.position += 2,
+                b'\n' => break,
+                _ => self.position += 1,
+            }
+        }
+
+        self.position = self.position.min(self.bytes.len());
+        TokenKind::Unknown
+    }
+
+    fn scan_number(&mut self) -> TokenKind {
+        while let Some(&byte) = self.bytes.get(self.position) {
+            match byte {
+                b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9' => self.position += 1,
+                _ => break,
+            }
+        }
+
+        TokenKind::Number
+    }
+
+    fn scan_word(&mut self) -> TokenKind {
+        let start = self.position;
+        while let Some(&byte) = self.bytes.get(self.position) {
+            if byte.is_ascii_alphanumeric() || byte == b'_' {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+
+        if self.position == start {
+            // Skip a whole (possibly multi-byte) character.
+            let ch_len = self.text[start..].chars().next().map_or(1, char::len_utf8);
+            self.position += ch_len;
+            return TokenKind::Unknown;
+        }
+
+        match &self.text[start..self.position] {
+            "true" => TokenKind::True,
+            "false" => TokenKind::False,
+            "null" => TokenKind::Null,
+            _ => TokenKind::Unknown,
+        }
+    }
+}
+
+/// Returns the 1-based line and column of the byte offset in the text.
+pub fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(text.len());
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = before[line_start..].chars().count() + 1;
+
+    (line, column)
+}
+
+/// An error found while parsing a JSONC text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// Byte offset of the erroneous token.
+    pub offset: usize,
+    /// Byte length of the erroneous token.
+    pub length: usize,
+    /// 1-based line of the erroneous token.
+    pub line: usize,
+    /// 1-based column of the erroneous token.
+    pub column: usize,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Result of parsing a JSONC text.
+/// The parser recovers from errors, so the tree contains everything that could be read
+/// even when `errors` is not empty.
+#[derive(Debug, Clone)]
+pub struct ParseResult {
+    /// The root node, `None` if the text contains no value at all.
+    pub root: Option<Node>,
+    pub errors: Vec<ParseError>,
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    scanner: Scanner<'a>,
+    token: Token,
+    errors: Vec<ParseError>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut parser = Self {
+            text,
+            scanner: Scanner::new(text),
+            token: Token {
+                kind: TokenKind::Eof,
+                offset: 0,
+                length: 0,
+            },
+            errors: Vec::new(),
+        };
+        parser.advance();
+
+        parser
+    }
+
+    fn advance(&mut self) -> Token {
+        let previous = self.token;
+        self.token = self.scanner.scan();
+
+        for offset in self.scanner.unterminated_comments.drain(..) {
+            let (line, column) = line_column(self.text, offset);
+            self.errors.push(ParseError {
+                message: "Unexpected end of comment".to_string(),
+                offset,
+                length: 2,
+                line,
+                column,
+            });
+        }
+
+        previous
+    }
+
+    fn error_at(&mut self, token: Token, message: impl Into<String>) {
+        let (line, column) = line_column(self.text, token.offset);
+        self.errors.push(ParseError {
+            message: message.into(),
+            offset: token.offset,
+            length: token.length,
+            line,
+            column,
+        });
+    }
+
+    fn error(&mut self, message: impl Into<String>) {
+        self.error_at(self.token, message);
+    }
+
+    /// Skips tokens until one of the given kinds (or the end of the text) is reached.
+    fn skip_until(&mut self, kinds: &[TokenKind]) {
+        while self.token.kind != TokenKind::Eof && !kinds.contains(&self.token.kind) {
+            self.advance();
+        }
+    }
+
+    fn parse_document(mut self) -> ParseResult {
+        if self.token.kind == TokenKind::Eof {
+            return ParseResult {
+                root: None,
+                errors: self.errors,
+            };
+        }
+
+        let root = self.parse_value();
+        if self.token.kind != TokenKind::Eof {
+            self.error("End of file expected");
+        }
+
+        ParseResult {
+            root,
+            errors: self.errors,
+        }
+    }
+
+    fn parse_value(&mut self) -> Option<Node> {
+        match self.token.kind {
+            TokenKind::OpenBrace => Some(self.parse_object()),
+            TokenKind::OpenBracket => Some(self.parse_array()),
+            TokenKind::String => self.parse_string(),
+            TokenKind::Number => {
+                let token = self.advance();
+                let raw = &self.text[token.offset..token.offset + token.length];
+                match serde_json::from_str::<serde_json::Number>(raw) {
+                    Ok(number) => Some(literal(NodeKind::Number, token, Value::Number(number))),
+                    Err(_) => {
+                        self.error_at(token, format!("Invalid number `{}`", raw));
+                        None
+                    }
+                }
+            }
+            TokenKind::True => Some(literal(
+                NodeKind::Boolean,
+                self.advance(),
+                Value::Bool(true),
+            )),
+            TokenKind::False => Some(literal(
+                NodeKind::Boolean,
+                self.advance(),
+                Value::Bool(false),
+            )),
+            TokenKind::Null => Some(literal(NodeKind::Null, self.advance(), Value::Null)),
+            TokenKind::Comma | TokenKind::CloseBrace | TokenKind::CloseBracket | TokenKind::Eof => {
+                // Leave the token to the enclosing object or array.
+                self.error("Value expected");
+                None
+            }
+            _ => {
+                self.error("Value expected");
+                self.advance();
+                None
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Option<Node> {
+        let token = self.advance();
+        let raw = &self.text[token.offset..token.offset + token.length];
+
+        match serde_json::from_str::<String>(raw) {
+            Ok(value) => Some(literal(NodeKind::String, token, Value::String(value))),
+            Err(_) => {
+                self.error_at(token, "Invalid string");
+                None
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Node {
+        let start = self.advance();
+        let mut children = Vec::new();
+
+        loop {
+            match self.token.kind {
+                TokenKind::CloseBrace => break,
+                TokenKind::Eof => {
+                    self.error("Closing brace expected");
+                    return self.node(NodeKind::Object, start, children);
+                }
+                TokenKind::String => {
+                    if let Some(property) = self.parse_property() {
+                        children.push(property);
+                    } else {
+                        self.skip_until(&[TokenKind::Comma, TokenKind::CloseBrace]);
+                    }
+                }
+                TokenKind::Comma => self.error("Property expected"),
+                _ => {
+                    self.error("Property name expected");
+                    self.skip_until(&[TokenKind::Comma, TokenKind::CloseBrace]);
+                }
+            }
+
+            match self.token.kind {
+                // Trailing commas are allowed in JSONC.
+                TokenKind::Comma => {
+                    self.advance();
+                }
+                TokenKind::CloseBrace => break,
+                TokenKind::Eof => {}
+                // A missing comma between two properties, keep reading the next one.
+                TokenKind::String => self.error("Comma expected"),
+                _ => {
+                    self.error("Comma expected");
+                    self.skip_until(&[TokenKind::Comma, TokenKind::CloseBrace]);
+                }
+            }
+        }
+
+        let end = self.advance();
+
+        Node {
+            kind: NodeKind::Object,
+            offset: start.offset,
+            length: end.offset + end.length - start.offset,
+            value: None,
+            children,
+        }
+    }
+
+    fn parse_property(&mut self) -> Option<Node> {
+        let key = self.parse_string()?;
+
+        if self.token.kind == TokenKind::Colon {
+            self.advance();
+        } else {
+            self.error("Colon expected");
+            if !matches!(
+                self.token.kind,
+                TokenKind::OpenBrace
+                    | TokenKind::OpenBracket
+                    | TokenKind::String
+                    | TokenKind::Number
+                    | TokenKind::True
+                    | TokenKind::False
+                    | TokenKind::Null
+            ) {
+                return None;
+            }
+        }
+
+        let value = self.parse_value()?;
+
+        Some(Node {
+            kind: NodeKind::Property,
+            offset: key.offset,
+            length: value.end() - key.offset,
+            value: None,
+            children: vec![key, value],
+        })
+    }
+
+    fn parse_array(&mut self) -> Node {
+        let start = self.advance();
+        let mut children = Vec::new();
+
+        loop {
+            match self.token.kind {
+                TokenKind::CloseBracket => break,
+                TokenKind::Eof => {
+                    self.error("Closing bracket expected");
+                    return self.node(NodeKind::Array, start, children);
+                }
+                _ => {
+                    if let Some(value) = self.parse_value() {
+                        children.push(value);
+                    }
+                }
+            }
+
+            match self.token.kind {
+                TokenKind::Comma => {
+                    self.advance();
+                }
+                TokenKind::CloseBracket | TokenKind::Eof => {}
+                _ => {
+                    self.error("Comma expected");
+                    self.skip_until(&[TokenKind::Comma, TokenKind::CloseBracket]);
+                }
+            }
+        }
+
+        let end = self.advance();
+
+        Node {
+            kind: NodeKind::Array,
+            offset: start.offset,
+            length: end.offset + end.length - start.offset,
+            value: None,
+            children,
+        }
+    }
+
+    /// Creates a node for an object or array that was not closed before the end of the text.
+    fn node(&self, kind: NodeKind, start: Token, children: Vec<Node>) -> Node {
+        Node {
+            kind,
+            offset: start.offset,
+            length: self.text.len() - start.offset,
+            value: None,
+            children,
+        }
+    }
+}
+
+fn literal(kind: NodeKind, token: Token, value: Value) -> Node {
+    Node {
+        kind,
+        offset: token.offset,
+        length: token.length,
+        value: Some(value),
+        children: Vec::new(),
+    }
+}
+
+/// Parses a JSON text that may contain comments and trailing commas into a document tree.
+/// Syntax errors don't stop the parser: it skips the broken part, records an error
+/// and keeps reading, so everything that is readable ends up in the tree.
+pub fn parse_tree(text: &str) -> ParseResult {
+    Parser::new(text).parse_document()
+}
+
+/// A single text replacement produced by `modify`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub offset: usize,
+    pub length: usize,
+    pub content: String,
+}
+
+/// Options used to format values inserted into a document.
+#[derive(Debug, Clone)]
+pub struct FormattingOptions {
+    /// The string used for one level of indentation.
+    pub indent: String,
+    /// The line terminator.
+    pub eol: String,
+}
+
+impl Default for FormattingOptions {
+    fn default() -> Self {
+        Self {
+            indent: "    ".to_string(),
+            eol: "\n".to_string(),
+        }
+    }
+}
+
+impl FormattingOptions {
+    /// Detects the indentation and line terminator already used by the text,
+    /// falling back to the defaults when the text gives no hint.
+    pub fn detect(text: &str) -> Self {
+        let default = Self::default();
+
+        let eol = if text.contains("\r\n") {
+            "\r\n".to_string()
+        } else {
+            default.eol
+        };
+
+        let indent = text
+            .lines()
+            .map(|line| &line[..line.len() - line.trim_start().len()])
+            .find(|leading| !leading.is_empty())
+            .map_or(default.indent, |leading| {
+                if leading.starts_with('\t') {
+                    "\t".to_string()
+                } else {
+                    leading.to_string()
+                }
+            });
+
+        Self { indent, eol }
+    }
+}
+
+/// Computes the edits needed to set (`Some`) or remove (`None`) the value at the given
+/// path of object keys, leaving comments and formatting of the rest of the document untouched.
+/// Missing intermediate objects are created.
+pub fn modify(
+    text: &str,
+    path: &[String],
+    value: Option<&Value>,
+    options: &FormattingOptions,
+) -> Result<Vec<Edit>> {
+    if path.is_empty() {
+        return Err(anyhow!("the path to modify must not be empty"));
+    }
+
+    let ParseResult { root, errors } = parse_tree(text);
+    if let Some(error) = errors.into_iter().next() {
+        // Editing a broken document could make things worse, so leave it to the user.
+        return Err(error.into());
+    }
+
+    let root = match root {
+        Some(root) => root,
+        None => {
+            let Some(value) = value else {
+                return Ok(Vec::new());
+            };
+
+            return Ok(vec![Edit {
+                offset: 0,
+                length: text.len(),
+                content: format_value(&nest(path, value), "", options),
+            }]);
+        }
+    };
+
+    if root.kind != NodeKind::Object {
+        return Err(anyhow!("the root of the document is not an object"));
+    }
+
+    let mut parent = &root;
+    let mut index = 0;
+    while index < path.len() - 1 {
+        match parent
+            .find_property(&path[index])
+            .and_then(Node::property_value)
+        {
+            Some(node) if node.kind == NodeKind::Object => {
+                parent = node;
+                index += 1;
+            }
+            _ => break,
+        }
+    }
+
+    let key = &path[index];
+    let value = match value {
+        Some(value) => nest(&path[index + 1..], value),
+        // There is nothing to remove if the path is only partially present.
+        None if index < path.len() - 1 => return Ok(Vec::new()),
+        None => return Ok(remove_property(text, parent, key)),
+    };
+
+    match parent.find_property(key) {
+        Some(property) => {
+            let value_node = property
+                .property_value()
+                .ok_or_else(|| anyhow!("property `{}` has no value", key))?;
+            let indent = line_indent(text, property.offset);
+
+            Ok(vec![Edit {
+                offset: value_node.offset,
+                length: value_node.length,
+                content: format_value(&value, indent, options),
+            }])
+        }
+        None => Ok(insert_property(text, parent, key, &value, options)),
+    }
+}
+
+/// Applies the edits to the text. Edits must not overlap.
+pub fn apply_edits(text: &str, edits: &[Edit]) -> String {
+    let mut sorted = edits.to_vec();
+    sorted.sort_by_key(|edit| std::cmp::Reverse(edit.offset));
+
+    let mut result = text.to_string();
+    for edit in sorted {
+        result.replace_range(edit.offset..edit.offset + edit.length, &edit.content);
+    }
+
+    result
+}
+
+fn nest(path: &[String], value: &Value) -> Value {
+    path.iter().rev().fold(value.clone(), |value, key| {
+        let mut map = serde_json::Map::new();
+        map.insert(key.clone(), value);
+
+        Value::Object(map)
+    })
+}
+
+fn remove_property(text: &str, parent: &Node, key: &str) -> Vec<Edit> {
+    let Some(index) = parent
+        .children
+        .iter()
+        .rposition(|property| property.property_key() == Some(key))
+    else {
+        return Vec::new();
+    };
+
+    let property = &parent.children[index];
+    let previous = index.checked_sub(1).map(|index| &parent.children[index]);
+    let next = parent.children.get(index + 1);
+
+    // Comments on the lines around the property belong to its neighbours, so a property
+    // written on its own lines is removed together with these lines only.
+    if let Some(edits) = remove_property_lines(text, property, previous, next) {
+        return edits;
+    }
+
+    let (start, end) = match (previous, next) {
+        // Remove the separating comma together with the property.
+        (Some(previous), _) => (previous.end(), property.end()),
+        (None, Some(next)) => (property.offset, next.offset),
+        // The only property is removed, so the object collapses to `{}`.
+        (None, None) => (parent.offset + 1, parent.end() - 1),
+    };
+
+    vec![delete(start, end)]
+}
+
+/// Returns the edits removing the lines of the property, with its trailing comment,
+/// or `None` if the property shares its lines with other content.
+fn remove_property_lines(
+    text: &str,
+    property: &Node,
+    previous: Option<&Node>,
+    next: Option<&Node>,
+) -> Option<Vec<Edit>> {
+    let line_start = text[..property.offset].rfind('\n').map_or(0, |i| i + 1);
+    if !text[line_start..property.offset].trim().is_empty() {
+        return None;
+    }
+
+    let mut edits = Vec::new();
+    let mut end = property.end();
+    match comma_after(text, end) {
+        Some(comma) => end = comma + 1,
+        // The last property is removed, so the comma of the previous one goes away too.
+        None if next.is_none() => {
+            let comma = previous.and_then(|previous| comma_after(text, previous.end()))?;
+            edits.push(delete(comma, comma + 1));
+        }
+        None => return None,
+    }
+
+    let line_end = text[end..].find('\n').map_or(text.len(), |i| end + i + 1);
+    let rest = text[end..line_end].trim();
+    if !rest.is_empty() && !is_comment(rest) {
+        return None;
+    }
+
+    edits.push(delete(line_start, line_end));
+    Some(edits)
+}
+
+fn insert_property(
+    text: &str,
+    parent: &Node,
+    key: &str,
+    value: &Value,
+    options: &FormattingOptions,
+) -> Vec<Edit> {
+    let escaped_key = Value::String(key.to_string()).to_string();
+
+    match parent.children.last() {
+        Some(last) => {
+            if is_same_line(text, parent.offset, last.offset) {
+                // The object is written on a single line, keep it that way.
+                vec![insert(last.end(), format!(", {}: {}", escaped_key, value))]
+            } else {
+                let indent = line_indent(text, last.offset);
+                let property = format!(
+                    "{}{}{}: {}",
+                    options.eol,
+                    indent,
+                    escaped_key,
+                    format_value(value, indent, options)
+                );
+
+                // The new property goes after the comment that ends the line of the last one,
+                // so the comment stays with the property it describes.
+                let comma = comma_after(text, last.end());
+                let line_offset = comma.map_or(last.end(), |comma| comma + 1);
+                match (comma, trailing_comment_end(text, line_offset)) {
+                    (Some(_), comment_end) => {
+                        vec![insert(comment_end.unwrap_or(line_offset), property)]
+                    }
+                    (None, Some(comment_end)) => vec![
+                        insert(last.end(), ",".to_string()),
+                        insert(comment_end, property),
+                    ],
+                    (None, None) => vec![insert(last.end(), format!(",{}", property))],
+                }
+            }
+        }
+        None => {
+            let parent_indent = line_indent(text, parent.offset);
+            let indent = format!("{}{}", parent_indent, options.indent);
+            let inner = &text[parent.offset + 1..parent.end() - 1];
+
+            // Keep anything already written inside the empty object (comments) in front of the new property.
+            let (offset, length) = if inner.trim().is_empty() {
+                (parent.offset + 1, inner.len())
+            } else {
+                (
+                    parent.offset + 1 + inner.trim_end().len(),
+                    inner.len() - inner.trim_end().len(),
+                )
+            };
+
+            vec![Edit {
+                offset,
+                length,
+                content: format!(
+                    "{}{}{}: {}{}{}",
+                    options.eol,
+                    indent,
+                    escaped_key,
+                    format_value(value, &indent, options),
+                    options.eol,
+                    parent_indent
+                ),
+            }]
+        }
+    }
+}
+
+fn insert(offset: usize, content: String) -> Edit {
+    Edit {
+        offset,
+        length: 0,
+        content,
+    }
+}
+
+fn delete(start: usize, end: usize) -> Edit {
+    Edit {
+        offset: start,
+        length: end - start,
+        content: String::new(),
+    }
+}
+
+/// Returns the offset of the comma following the offset on the same line, if any.
+fn comma_after(text: &str, offset: usize) -> Option<usize> {
+    let rest = &text[offset..];
+    let comma = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+
+    rest[comma..].starts_with(',').then_some(offset + comma)
+}
+
+/// Returns the end of the comment taking the rest of the line after the offset, if any.
+fn trailing_comment_end(text: &str, offset: usize) -> Option<usize> {
+    let line_end = text[offset..].find('\n').map_or(text.len(), |i| offset + i);
+    let rest = &text[offset..line_end];
+
+    is_comment(rest.trim()).then(|| offset + rest.trim_end().len())
+}
+
+fn is_comment(text: &str) -> bool {
+    text.starts_with("//")
+        || (text.len() >= 4
+            && text.starts_with("/*")
+            && text.ends_with("*/")
+            && !text[2..text.len() - 2].contains("*/"))
+}
+
+fn is_same_line(text: &str, a: usize, b: usize) -> bool {
+    let (start, end) = if a < b { (a, b) } else { (b, a) };
+    !text[start..end].contains('\n')
+}
+
+/// Returns the leading whitespace of the line containing the offset.
+fn line_indent(text: &str, offset: usize) -> &str {
+    let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = &text[line_start..];
+
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+fn format_value(value: &Value, base_indent: &str, options: &FormattingOptions) -> String {
+    let mut buffer = Vec::new();
+    let formatter = PrettyFormatter::with_indent(options.indent.as_bytes());
+    let mut serializer = Serializer::with_formatter(&mut buffer, formatter);
+
+    if value.serialize(&mut serializer).is_err() {
+        return value.to_string();
+    }
+
+    String::from_utf8(buffer)
+        .unwrap_or_else(|_| value.to_string())
+        .replace('\n', &format!("{}{}", options.eol, base_indent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn set(text: &str, path: &[&str], value: Option<Value>) -> String {
+        let path: Vec<String> = path.iter().map(|s| s.to_string()).collect();
+        let edits = modify(
+            text,
+            &path,
+            value.as_ref(),
+            &FormattingOptions::detect(text),
+        )
+        .unwrap();
+
+        apply_edits(text, &edits)
+    }
+
+    #[test]
+    fn test_parse_tree_with_comments_and_trailing_commas() {
+        let text = r#"{
+    // line comment
+    "editor.fontSize": 12, /* block comment */
+    "[rust]": { "editor.tabSize": 4, },
+}"#;
+        let result = parse_tree(text);
+        assert!(result.errors.is_empty());
+        let root = result.root.unwrap();
+
+        assert_eq!(
+            root.to_value(),
+            json!({ "editor.fontSize": 12, "[rust]": { "editor.tabSize": 4 } })
+        );
+    }
+
+    #[test]
+    fn test_parse_tree_error_position() {
+        let result = parse_tree("{\n  \"a\": 1\n  \"b\": 2\n}");
+
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].message, "Comma expected");
+        assert_eq!((result.errors[0].line, result.errors[0].column), (3, 3));
+        assert_eq!(result.root.unwrap().to_value(), json!({ "a": 1, "b": 2 }));
+    }
+
+    #[test]
+    fn test_parse_tree_recovers_from_errors() {
+        let text = r#"{
+    "a": 1,
+    "b": ,
+    "c" 3,
+    "d": [1, 2,, 3],
+    garbage,
+    "e": true
+"#;
+        let result = parse_tree(text);
+
+        assert_eq!(
+            result.root.unwrap().to_value(),
+            json!({ "a": 1, "c": 3, "d": [1, 2, 3], "e": true })
+        );
+
+        let messages: Vec<_> = result
+            .errors
+            .iter()
+            .map(|error| (error.message.as_str(), error.line))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                ("Value expected", 3),
+                ("Colon expected", 4),
+                ("Value expected", 5),
+                ("Property name expected", 6),
+                ("Closing brace expected", 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_tree_unterminated_comment() {
+        let result = parse_tree("{ \"a\": 1 } /* comment");
+
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].message, "Unexpected end of comment");
+        assert_eq!(result.root.unwrap().to_value(), json!({ "a": 1 }));
+    }
+
+    #[test]
+    fn test_modify_broken_document() {
+        let path = vec!["a".to_string()];
+        let result = modify(
+            "{ \"a\": }",
+            &path,
+            Some(&json!(1)),
+            &FormattingOptions::default(),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_modify_replace_value_keeps_comments() {
+        let text = "{\n    // the font size\n    \"editor.fontSize\": 12\n}";
+        let result = set(text, &["editor.fontSize"], Some(json!(14)));
+
+        assert_eq!(
+            result,
+            "{\n    // the font size\n    \"editor.fontSize\": 14\n}"
+        );
+    }
+
+    #[test]
+    fn test_modify_insert_property() {
+        let text = "{\n  \"a\": 1 // keep me\n}";
+        let result = set(text, &["b"], Some(json!(true)));
+
+        assert_eq!(result, "{\n  \"a\": 1, // keep me\n  \"b\": true\n}");
+
+        let text = "{\n  \"a\": 1, /* keep me */\n}";
+        let result = set(text, &["b"], Some(json!(true)));
+        assert_eq!(result, "{\n  \"a\": 1, /* keep me */\n  \"b\": true\n}");
+    }
+
+    #[test]
+    fn test_modify_insert_into_empty_document() {
+        assert_eq!(set("", &["a"], Some(json!(1))), "{\n    \"a\": 1\n}");
+        assert_eq!(set("{}", &["a"], Some(json!(1))), "{\n    \"a\": 1\n}");
+    }
+
+    #[test]
+    fn test_modify_insert_override_key() {
+        let text = "{\n  \"a\": 1\n}";
+        let result = set(text, &["[rust]", "editor.tabSize"], Some(json!(2)));
+
+        assert_eq!(
+            result,
+            "{\n  \"a\": 1,\n  \"[rust]\": {\n    \"editor.tabSize\": 2\n  }\n}"
+        );
+
+        let result = set(&result, &["[rust]", "editor.fontSize"], Some(json!(10)));
+        assert_eq!(
+            result,
+            "{\n  \"a\": 1,\n  \"[rust]\": {\n    \"editor.tabSize\": 2,\n    \"editor.fontSize\": 10\n  }\n}"
+        );
+    }
+
+    #[test]
+    fn test_modify_remove_property() {
+        let text = "{\n  \"a\": 1,\n  \"b\": 2,\n  \"c\": 3\n}";
+
+        assert_eq!(set(text, &["b"], None), "{\n  \"a\": 1,\n  \"c\": 3\n}");
+        assert_eq!(set(text, &["a"], None), "{\n  \"b\": 2,\n  \"c\": 3\n}");
+        assert_eq!(set("{ \"a\": 1 }", &["a"], None), "{}");
+        assert_eq!(set(text, &["missing"], None), text);
+    }
+
+    #[test]
+    fn test_modify_remove_property_keeps_comments() {
+        let text =
+            "{\n  \"a\": 1, // about a\n  // about b\n  \"b\": 2, // b\n  \"c\": 3 // about c\n}";
+
+        assert_eq!(
+            set(text, &["b"], None),
+            "{\n  \"a\": 1, // about a\n  // about b\n  \"c\": 3 // about c\n}"
+        );
+        assert_eq!(
+            set(text, &["c"], None),
+            "{\n  \"a\": 1, // about a\n  // about b\n  \"b\": 2 // b\n}"
+        );
+    }
+
+    #[test]
+    fn test_modify_single_line_object() {
+        let result = set("{ \"a\": 1 }", &["b"], Some(json!("x")));
+        assert_eq!(result, "{ \"a\": 1, \"b\": \"x\" }");
+    }
+}
@@ -1,10 +1,22 @@
+pub mod configuration_bundle;
+pub mod configuration_contribution;
 pub mod configuration_default;
+pub mod configuration_diagnostic;
+pub mod configuration_editing;
+pub mod configuration_error;
+pub mod configuration_key;
+pub mod configuration_migration;
 pub mod configuration_model;
 pub mod configuration_parser;
+pub mod configuration_platform;
 pub mod configuration_policy;
 pub mod configuration_registry;
+pub mod configuration_search;
 pub mod configuration_service;
+pub mod jsonc;
 pub mod policy;
+pub mod policy_file;
+pub mod typed_settings;
 pub mod user_settings;
 
 #[macro_use]
@@ -17,15 +29,19 @@ extern crate async_trait;
 extern crate lazy_regex;
 
 use anyhow::Result;
-use configuration_model::AttributeName;
+use configuration_key::ConfigurationKey;
+use platform_core::context_v2::Context;
 
-#[async_trait]
 pub trait AbstractConfigurationService {
-    fn get_value(&self, attribute_name: AttributeName) -> Option<serde_json::Value>;
+    fn get_value(&self, attribute_name: ConfigurationKey) -> Option<serde_json::Value>;
 
-    async fn update_value(
+    /// Returns all values under the section as a nested object, see `Configuration::get_section`.
+    fn get_section(&self, section: ConfigurationKey) -> Option<serde_json::Value>;
+
+    fn update_value(
         &self,
-        attribute_name: AttributeName,
+        ctx: &mut Context,
+        attribute_name: ConfigurationKey,
         value: &serde_json::Value,
     ) -> Result<()>;
 }
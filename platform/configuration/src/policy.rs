@@ -1,8 +1,90 @@
-use serde_json::Number;
+use anyhow::Result;
+use serde_json::Value;
 
+use crate::configuration_registry::ConfigurationNodeType;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum PolicyDefinitionType {
     String,
     Number,
+    Bool,
+    /// A value that must be one of the listed values.
+    Enum(Vec<Value>),
+    Array,
+    Object,
+}
+
+impl PolicyDefinitionType {
+    /// Checks whether the value has the type required by the policy definition.
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            PolicyDefinitionType::String => value.is_string(),
+            PolicyDefinitionType::Number => value.is_number(),
+            PolicyDefinitionType::Bool => value.is_boolean(),
+            PolicyDefinitionType::Enum(values) => values.contains(value),
+            PolicyDefinitionType::Array => value.is_array(),
+            PolicyDefinitionType::Object => value.is_object(),
+        }
+    }
+
+    /// Same as `matches`, with an error describing the mismatch.
+    pub fn validate(&self, value: &Value) -> Result<()> {
+        if self.matches(value) {
+            return Ok(());
+        }
+
+        Err(anyhow!(
+            "expected {}, found {}",
+            self,
+            describe_value_type(value)
+        ))
+    }
+
+    /// Checks whether the values allowed by the policy definition can be assigned
+    /// to a configuration property of the given type.
+    pub fn is_compatible_with(&self, typ: &ConfigurationNodeType) -> bool {
+        match (self, typ) {
+            (PolicyDefinitionType::String, ConfigurationNodeType::String)
+            | (PolicyDefinitionType::Number, ConfigurationNodeType::Number)
+            | (PolicyDefinitionType::Bool, ConfigurationNodeType::Bool)
+            | (PolicyDefinitionType::Array, ConfigurationNodeType::Array)
+            | (PolicyDefinitionType::Object, ConfigurationNodeType::Object) => true,
+            (PolicyDefinitionType::Enum(values), typ) => {
+                values.iter().all(|value| typ.matches(value))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for PolicyDefinitionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolicyDefinitionType::String => write!(f, "a string"),
+            PolicyDefinitionType::Number => write!(f, "a number"),
+            PolicyDefinitionType::Bool => write!(f, "a boolean"),
+            PolicyDefinitionType::Enum(values) => {
+                let values = values
+                    .iter()
+                    .map(|value| format!("`{}`", value))
+                    .collect::<Vec<_>>();
+                write!(f, "one of {}", values.join(", "))
+            }
+            PolicyDefinitionType::Array => write!(f, "an array"),
+            PolicyDefinitionType::Object => write!(f, "an object"),
+        }
+    }
+}
+
+fn describe_value_type(value: &Value) -> String {
+    match value {
+        Value::Null => String::from("null"),
+        Value::Bool(_) => String::from("a boolean"),
+        Value::Number(_) => String::from("a number"),
+        Value::String(_) => format!("the string {}", value),
+        Value::Array(_) => String::from("an array"),
+        Value::Object(_) => String::from("an object"),
+    }
 }
 
 pub trait PolicyService {
@@ -16,3 +98,33 @@ impl PolicyService for EmptyPolicyService {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_enum() {
+        let definition = PolicyDefinitionType::Enum(vec![json!("stable"), json!("insiders")]);
+
+        assert!(definition.validate(&json!("stable")).is_ok());
+        assert_eq!(
+            definition
+                .validate(&json!("nightly"))
+                .unwrap_err()
+                .to_string(),
+            "expected one of `\"stable\"`, `\"insiders\"`, found the string \"nightly\""
+        );
+    }
+
+    #[test]
+    fn test_is_compatible_with() {
+        assert!(PolicyDefinitionType::Bool.is_compatible_with(&ConfigurationNodeType::Bool));
+        assert!(!PolicyDefinitionType::Bool.is_compatible_with(&ConfigurationNodeType::String));
+        assert!(PolicyDefinitionType::Enum(vec![json!("a"), json!("b")])
+            .is_compatible_with(&ConfigurationNodeType::String));
+        assert!(!PolicyDefinitionType::Enum(vec![json!("a"), json!(1)])
+            .is_compatible_with(&ConfigurationNodeType::String));
+    }
+}
@@ -0,0 +1,265 @@
+use anyhow::Result;
+use futures::Stream;
+use hashbrown::HashMap;
+use platform_core::context_v2::Context;
+use platform_fs::disk::file_system_service::AbstractDiskFileSystemService;
+use serde_json::Value;
+use std::{io::Read, path::PathBuf, pin::Pin, sync::Arc, time::Duration};
+
+use crate::{
+    configuration_diagnostic::{ConfigurationDiagnostic, DiagnosticSpan},
+    configuration_policy::ConfigurationPolicyService,
+    jsonc::{self, NodeKind},
+    policy::PolicyDefinitionType,
+    user_settings::watch_resource,
+};
+
+/// Reads the policies set by an administrator from a managed file.
+/// The file is an object mapping policy names to values, written in JSON (comments are allowed)
+/// or in TOML if the file has the `.toml` extension. A missing file means that no policies are set.
+pub struct FilePolicyProvider {
+    resource: PathBuf,
+    definitions: HashMap<String, PolicyDefinitionType>,
+    fs_service: Arc<dyn AbstractDiskFileSystemService>,
+}
+
+impl FilePolicyProvider {
+    pub fn new(
+        resource: PathBuf,
+        definitions: HashMap<String, PolicyDefinitionType>,
+        fs_service: Arc<dyn AbstractDiskFileSystemService>,
+    ) -> Self {
+        Self {
+            resource,
+            definitions,
+            fs_service,
+        }
+    }
+
+    /// The well-known location of the policy file on the current platform.
+    pub fn default_resource() -> PathBuf {
+        if cfg!(target_os = "windows") {
+            PathBuf::from(r"C:\ProgramData\Moss\policies.json")
+        } else if cfg!(target_os = "macos") {
+            PathBuf::from("/Library/Application Support/Moss/policies.json")
+        } else {
+            PathBuf::from("/etc/moss/policies.json")
+        }
+    }
+
+    pub fn resource(&self) -> &PathBuf {
+        &self.resource
+    }
+
+    pub fn load(&self, ctx: &mut Context) -> Result<ConfigurationPolicyService> {
+        if !ctx.block_on_with(self.fs_service.file_exists(&self.resource)) {
+            return Ok(ConfigurationPolicyService::new(self.definitions.clone()));
+        }
+
+        let mut file = ctx.block_on_with(self.fs_service.read_file(&self.resource))?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+
+        let mut policy_service = self.parse(&content);
+        for diagnostic in policy_service.diagnostics.iter_mut() {
+            diagnostic.source = Some(self.resource.clone());
+        }
+
+        Ok(policy_service)
+    }
+
+    /// Returns a stream that yields every time the policy file changes on disk.
+    /// If the directory of the policy file doesn't exist, policies are not managed on this machine
+    /// and the stream never yields.
+    pub async fn watch(&self, latency: Duration) -> Result<Pin<Box<dyn Send + Stream<Item = ()>>>> {
+        let parent = self.resource.parent().map(PathBuf::from);
+        if let Some(parent) = parent {
+            if !self.fs_service.file_exists(&parent).await {
+                return Ok(Box::pin(futures::stream::pending()));
+            }
+        }
+
+        watch_resource(self.fs_service.as_ref(), &self.resource, latency).await
+    }
+
+    /// Parses the content of the policy file. Values of unknown policies and values
+    /// that don't match their definition are dropped and reported as diagnostics.
+    fn parse(&self, content: &str) -> ConfigurationPolicyService {
+        let mut policy_service = ConfigurationPolicyService::new(self.definitions.clone());
+
+        let entries = if self.is_toml() {
+            Self::read_toml_entries(content, &mut policy_service.diagnostics)
+        } else {
+            Self::read_json_entries(content, &mut policy_service.diagnostics)
+        };
+
+        for (name, value, span) in entries {
+            let diagnostic = match self.definitions.get(&name).map(|d| d.validate(&value)) {
+                Some(Ok(())) => {
+                    policy_service.policies.insert(name, value);
+                    continue;
+                }
+                Some(Err(err)) => ConfigurationDiagnostic::error(format!(
+                    "Invalid value of the policy `{}`: {}",
+                    name, err
+                )),
+                None => ConfigurationDiagnostic::warning(format!("Unknown policy `{}`", name)),
+            };
+
+            let diagnostic = match span {
+                Some(span) => diagnostic.with_span(span),
+                None => diagnostic,
+            };
+            policy_service.diagnostics.push(diagnostic.with_key(name));
+        }
+
+        policy_service
+    }
+
+    fn is_toml(&self) -> bool {
+        self.resource
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"))
+    }
+
+    fn read_json_entries(
+        content: &str,
+        diagnostics: &mut Vec<ConfigurationDiagnostic>,
+    ) -> Vec<(String, Value, Option<DiagnosticSpan>)> {
+        let result = jsonc::parse_tree(content);
+        diagnostics.extend(result.errors.into_iter().map(ConfigurationDiagnostic::from));
+
+        let root = match result.root {
+            Some(root) if root.kind == NodeKind::Object => root,
+            Some(root) => {
+                diagnostics.push(
+                    ConfigurationDiagnostic::error("The policies must be an object")
+                        .with_span(DiagnosticSpan::from_node(content, &root)),
+                );
+
+                return Vec::new();
+            }
+            None => return Vec::new(),
+        };
+
+        root.children
+            .iter()
+            .filter_map(|property| {
+                let name = property.property_key()?;
+                let value = property.property_value()?;
+
+                Some((
+                    name.to_string(),
+                    value.to_value(),
+                    Some(DiagnosticSpan::from_node(content, value)),
+                ))
+            })
+            .collect()
+    }
+
+    fn read_toml_entries(
+        content: &str,
+        diagnostics: &mut Vec<ConfigurationDiagnostic>,
+    ) -> Vec<(String, Value, Option<DiagnosticSpan>)> {
+        match toml::from_str::<serde_json::Map<String, Value>>(content) {
+            Ok(map) => map
+                .into_iter()
+                .map(|(name, value)| (name, value, None))
+                .collect(),
+            Err(err) => {
+                let diagnostic = ConfigurationDiagnostic::error(err.message());
+                diagnostics.push(match err.span() {
+                    Some(span) => {
+                        let (line, column) = jsonc::line_column(content, span.start);
+                        diagnostic.with_span(DiagnosticSpan {
+                            offset: span.start,
+                            length: span.len(),
+                            line,
+                            column,
+                        })
+                    }
+                    None => diagnostic,
+                });
+
+                Vec::new()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration_diagnostic::DiagnosticSeverity;
+    use platform_fs::disk::file_system_service::DiskFileSystemService;
+    use serde_json::json;
+
+    fn provider(resource: &str) -> FilePolicyProvider {
+        FilePolicyProvider::new(
+            PathBuf::from(resource),
+            HashMap::from_iter([
+                (
+                    "editorLineHeightPolicy".to_string(),
+                    PolicyDefinitionType::Number,
+                ),
+                (
+                    "updateChannelPolicy".to_string(),
+                    PolicyDefinitionType::String,
+                ),
+            ]),
+            Arc::new(DiskFileSystemService::new()),
+        )
+    }
+
+    #[test]
+    fn test_parse_json_policies() {
+        let policy_service = provider("policies.json").parse(
+            r#"{
+    // Managed by IT
+    "editorLineHeightPolicy": 45,
+    "updateChannelPolicy": 1,
+    "unknownPolicy": true
+}"#,
+        );
+
+        assert_eq!(
+            policy_service.policies.get("editorLineHeightPolicy"),
+            Some(&json!(45))
+        );
+        assert_eq!(policy_service.policies.get("updateChannelPolicy"), None);
+
+        let diagnostics = &policy_service.diagnostics;
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostics[0].key.as_deref(), Some("updateChannelPolicy"));
+        assert_eq!(diagnostics[0].span.as_ref().map(|span| span.line), Some(4));
+        assert_eq!(diagnostics[1].severity, DiagnosticSeverity::Warning);
+        assert_eq!(diagnostics[1].key.as_deref(), Some("unknownPolicy"));
+    }
+
+    #[test]
+    fn test_parse_toml_policies() {
+        let policy_service = provider("policies.toml")
+            .parse("editorLineHeightPolicy = 45\nupdateChannelPolicy = \"stable\"\n");
+
+        assert!(policy_service.diagnostics.is_empty());
+        assert_eq!(
+            policy_service.policies.get("editorLineHeightPolicy"),
+            Some(&json!(45))
+        );
+        assert_eq!(
+            policy_service.policies.get("updateChannelPolicy"),
+            Some(&json!("stable"))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_toml() {
+        let policy_service = provider("policies.toml").parse("editorLineHeightPolicy = ");
+
+        assert!(policy_service.policies.is_empty());
+        assert_eq!(policy_service.diagnostics.len(), 1);
+        assert!(policy_service.diagnostics[0].is_error());
+        assert!(policy_service.diagnostics[0].span.is_some());
+    }
+}
@@ -0,0 +1,321 @@
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::marker::PhantomData;
+
+use crate::{
+    configuration_error::ConfigurationError,
+    configuration_key::ConfigurationKey,
+    configuration_registry::{ConfigurationNode, ConfigurationNodeType},
+    AbstractConfigurationService,
+};
+
+/// A registered setting together with the type of its value.
+/// Keys are declared as constants next to the `ConfigurationNode` that registers them:
+///
+/// ```rust
+/// # use platform_configuration::typed_settings::SettingKey;
+/// pub const WINDOW_DEFAULT_WIDTH: SettingKey<u32> = SettingKey::new("window.defaultWidth");
+/// ```
+pub struct SettingKey<T> {
+    name: &'static str,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> SettingKey<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn attribute_name(&self) -> ConfigurationKey {
+        ConfigurationKey::new(self.name)
+    }
+}
+
+impl<T: DeserializeOwned> SettingKey<T> {
+    /// Checks that the node registers the setting with a type that can be read as `T`.
+    /// Keys are only names, so the crate declaring them should run this check in its tests.
+    pub fn check(&self, node: &ConfigurationNode) -> Result<()> {
+        let key = ConfigurationKey::new(self.name);
+        let schema = node
+            .properties
+            .iter()
+            .flatten()
+            .find_map(|(property_key, schema)| (*property_key == key).then_some(schema))
+            .ok_or_else(|| {
+                anyhow!(
+                    "setting `{}` is not registered by the `{}` node",
+                    self.name,
+                    node.id
+                )
+            })?;
+
+        // A `null` default is how a schema says that the setting has no default value.
+        let default = schema.default.as_ref().filter(|default| !default.is_null());
+        let value = match (&schema.typ, default) {
+            (Some(typ), Some(default)) if !typ.matches(default) => {
+                return Err(anyhow!(
+                    "the default value of setting `{}` is not of its {:?} type",
+                    self.name,
+                    typ
+                ));
+            }
+            (_, Some(default)) => default.clone(),
+            (Some(typ), None) => ConfigurationNodeType::default_value(typ),
+            (None, None) => return Ok(()),
+        };
+
+        from_value::<T>(self.name, Some(value)).map(|_| ())
+    }
+}
+
+impl<T> Clone for SettingKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SettingKey<T> {}
+
+/// A strongly-typed view of the settings registered by a `ConfigurationNode`.
+/// The struct is read from the section named after the node id, so the fields of
+/// `window` settings are `defaultWidth`, `defaultHeight` and so on (use `#[serde(rename_all = "camelCase")]`).
+pub trait Settings: DeserializeOwned {
+    fn node() -> &'static ConfigurationNode;
+
+    fn load<S: AbstractConfigurationService + ?Sized>(service: &S) -> Result<Self> {
+        let section = &Self::node().id;
+        let value = service.get_section(ConfigurationKey::new(section.clone()));
+
+        from_value(section, value)
+    }
+}
+
+/// Typed access to the values of a configuration service.
+/// A value of the wrong type is returned as `ConfigurationError::InvalidValue` instead of causing a panic.
+pub trait TypedConfigurationService: AbstractConfigurationService {
+    fn get<T: DeserializeOwned>(&self, attribute_name: ConfigurationKey) -> Result<T> {
+        let key = attribute_name.name.clone();
+        from_value(&key, self.get_value(attribute_name))
+    }
+
+    fn get_setting<T: DeserializeOwned>(&self, key: &SettingKey<T>) -> Result<T> {
+        from_value(key.name(), self.get_value(key.attribute_name()))
+    }
+
+    fn get_settings<T: Settings>(&self) -> Result<T> {
+        T::load(self)
+    }
+}
+
+impl<S: AbstractConfigurationService + ?Sized> TypedConfigurationService for S {}
+
+/// Converts a configuration value to `T`.
+/// A missing value is read as `null`, so it is `None` for `Option` types and an error otherwise.
+fn from_value<T: DeserializeOwned>(key: &str, value: Option<Value>) -> Result<T> {
+    let is_missing = value.is_none();
+
+    serde_json::from_value(value.unwrap_or(Value::Null)).map_err(|err| {
+        if is_missing {
+            ConfigurationError::MissingValue {
+                key: key.to_string(),
+            }
+        } else {
+            ConfigurationError::InvalidValue {
+                key: key.to_string(),
+                reason: err.to_string(),
+            }
+        }
+        .into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SettingKey, Settings, TypedConfigurationService};
+    use crate::attribute_name;
+    use crate::{
+        configuration_error::ConfigurationError,
+        configuration_key::ConfigurationKey,
+        configuration_model::{Configuration, ConfigurationModel},
+        configuration_registry::{
+            ConfigurationNode, ConfigurationNodeType, ConfigurationPropertySchema, PropertyMap,
+        },
+        property_key, AbstractConfigurationService,
+    };
+    use platform_core::context_v2::Context;
+    use serde::Deserialize;
+    use std::sync::{Arc, LazyLock};
+
+    const EDITOR_FONT_SIZE: SettingKey<u32> = SettingKey::new("editor.fontSize");
+    const EDITOR_FONT_FAMILY: SettingKey<Option<String>> = SettingKey::new("editor.fontFamily");
+    const EDITOR_WORD_WRAP: SettingKey<bool> = SettingKey::new("editor.wordWrap");
+
+    static EDITOR_NODE: LazyLock<ConfigurationNode> = LazyLock::new(|| ConfigurationNode {
+        id: "editor".to_string(),
+        scope: None,
+        order: None,
+        typ: None,
+        title: None,
+        description: None,
+        properties: {
+            let mut properties = PropertyMap::new();
+            properties.insert(
+                property_key!(editor.fontSize),
+                ConfigurationPropertySchema {
+                    typ: Some(ConfigurationNodeType::Number),
+                    default: Some(serde_json::json!(12)),
+                    ..Default::default()
+                },
+            );
+            properties.insert(
+                property_key!(editor.fontFamily),
+                ConfigurationPropertySchema {
+                    typ: Some(ConfigurationNodeType::String),
+                    ..Default::default()
+                },
+            );
+            properties.insert(
+                property_key!(editor.wordWrap),
+                ConfigurationPropertySchema {
+                    typ: Some(ConfigurationNodeType::String),
+                    default: Some(serde_json::json!("off")),
+                    ..Default::default()
+                },
+            );
+
+            Some(properties)
+        },
+        parent_of: None,
+        source: None,
+    });
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    struct EditorSettings {
+        font_size: u32,
+        tab_size: u32,
+    }
+
+    impl Settings for EditorSettings {
+        fn node() -> &'static ConfigurationNode {
+            &EDITOR_NODE
+        }
+    }
+
+    struct TestConfigurationService {
+        configuration: Configuration,
+    }
+
+    impl AbstractConfigurationService for TestConfigurationService {
+        fn get_value(&self, attribute_name: ConfigurationKey) -> Option<serde_json::Value> {
+            self.configuration.get_value(&attribute_name)
+        }
+
+        fn get_section(&self, section: ConfigurationKey) -> Option<serde_json::Value> {
+            self.configuration.get_section(&section)
+        }
+
+        fn update_value(
+            &self,
+            _ctx: &mut Context,
+            _attribute_name: ConfigurationKey,
+            _value: &serde_json::Value,
+        ) -> anyhow::Result<()> {
+            Err(anyhow!("read-only"))
+        }
+    }
+
+    fn test_service() -> TestConfigurationService {
+        let mut model = ConfigurationModel::empty();
+        model.set_value(
+            ConfigurationKey::new("editor.fontSize"),
+            serde_json::json!(14),
+        );
+        model.set_value(
+            ConfigurationKey::new("editor.tabSize"),
+            serde_json::json!(4),
+        );
+        model.set_value(
+            ConfigurationKey::new("editor.wordWrap"),
+            serde_json::json!("on"),
+        );
+
+        TestConfigurationService {
+            configuration: Configuration::new(
+                Arc::new(model),
+                Arc::new(ConfigurationModel::empty()),
+                Arc::new(ConfigurationModel::empty()),
+                Arc::new(ConfigurationModel::empty()),
+                Arc::new(ConfigurationModel::empty()),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_get_typed_values() {
+        let service = test_service();
+
+        assert_eq!(service.get_setting(&EDITOR_FONT_SIZE).unwrap(), 14);
+        assert_eq!(service.get_setting(&EDITOR_FONT_FAMILY).unwrap(), None);
+        assert_eq!(
+            service.get::<u32>(attribute_name!(editor.tabSize)).unwrap(),
+            4
+        );
+        assert_eq!(
+            service.get_settings::<EditorSettings>().unwrap(),
+            EditorSettings {
+                font_size: 14,
+                tab_size: 4
+            }
+        );
+    }
+
+    #[test]
+    fn test_get_typed_value_errors() {
+        let service = test_service();
+
+        let err = service.get_setting(&EDITOR_WORD_WRAP).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ConfigurationError>(),
+            Some(ConfigurationError::InvalidValue { key, .. }) if key == "editor.wordWrap"
+        ));
+
+        let err = service
+            .get::<u32>(attribute_name!(editor.lineHeight))
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ConfigurationError>(),
+            Some(&ConfigurationError::MissingValue {
+                key: "editor.lineHeight".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_setting_keys() {
+        EDITOR_FONT_SIZE.check(&EDITOR_NODE).unwrap();
+        EDITOR_FONT_FAMILY.check(&EDITOR_NODE).unwrap();
+
+        let err = EDITOR_WORD_WRAP.check(&EDITOR_NODE).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ConfigurationError>(),
+            Some(ConfigurationError::InvalidValue { key, .. }) if key == "editor.wordWrap"
+        ));
+
+        let err = SettingKey::<u32>::new("editor.lineHeight")
+            .check(&EDITOR_NODE)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "setting `editor.lineHeight` is not registered by the `editor` node"
+        );
+    }
+}
@@ -1,13 +1,25 @@
-use anyhow::Result;
+use anyhow::{Context as AnyhowContext, Result};
+use futures::{Stream, StreamExt};
 use platform_core::context_v2::Context;
-use platform_fs::disk::file_system_service::AbstractDiskFileSystemService;
-use std::{io::Read, path::PathBuf, sync::Arc};
+use platform_fs::{
+    common::file_system_service::WatchOptions,
+    disk::file_system_service::AbstractDiskFileSystemService,
+};
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
 
 use crate::{configuration_model::ConfigurationModel, configuration_parser::ConfigurationParser};
 
 // TODO:
 // - Use a LogService.
 // - Use a PolicyService
+/// Settings read from a settings file, e.g. the user or the workspace `settings.json`.
+/// A missing file is read as a file without settings.
 pub struct UserSettings {
     parser: Arc<ConfigurationParser>,
     resource: PathBuf,
@@ -28,7 +40,21 @@ impl<'a> UserSettings {
         }
     }
 
+    pub fn resource(&self) -> &PathBuf {
+        &self.resource
+    }
+
+    /// Returns a stream that yields every time the settings file changes on disk.
+    /// The parent directory is watched, so the file may be created, deleted or replaced atomically.
+    pub async fn watch(&self, latency: Duration) -> Result<Pin<Box<dyn Send + Stream<Item = ()>>>> {
+        watch_resource(self.fs_service.as_ref(), &self.resource, latency).await
+    }
+
     pub fn load_configuration(&self, ctx: &mut Context) -> Result<ConfigurationModel> {
+        if !ctx.block_on_with(self.fs_service.file_exists(&self.resource)) {
+            return Ok(ConfigurationModel::empty());
+        }
+
         let mut file = ctx.block_on_with(self.fs_service.read_file(&self.resource))?;
         let mut content = String::new();
         file.read_to_string(&mut content)?;
@@ -37,6 +63,39 @@ impl<'a> UserSettings {
             content = String::from("{}")
         }
 
-        Ok(self.parser.parse(ctx, &content)?)
+        let mut model = self.parser.parse(ctx, &content);
+        model.set_diagnostics_source(&self.resource);
+
+        Ok(model)
     }
 }
+
+/// Watches the parent directory of the resource and yields every time the resource changes.
+pub(crate) async fn watch_resource(
+    fs_service: &dyn AbstractDiskFileSystemService,
+    resource: &Path,
+    latency: Duration,
+) -> Result<Pin<Box<dyn Send + Stream<Item = ()>>>> {
+    let parent = resource
+        .parent()
+        .with_context(|| format!("{} has no parent directory", resource.display()))?
+        .to_path_buf();
+    let resource = resource.to_path_buf();
+
+    let options = WatchOptions {
+        latency,
+        ..Default::default()
+    };
+    let events = fs_service.watch(&parent, options).await?;
+    let result = events.filter_map(move |event| {
+        // The resource also changes when the directory holding it is removed or created.
+        let changed = event
+            .paths()
+            .into_iter()
+            .any(|path| resource.starts_with(path));
+
+        async move { changed.then_some(()) }
+    });
+
+    Ok(Box::pin(result))
+}
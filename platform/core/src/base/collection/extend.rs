@@ -8,7 +8,7 @@ pub trait Extend<T> {
 
 impl<K, V> Extend<(K, V)> for radix_trie::Trie<K, V>
 where
-    K: radix_trie::TrieKey + AsRef<str> + Hash + Eq,
+    K: radix_trie::TrieKey + Hash + Eq,
     V: Clone,
 {
     fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
@@ -21,7 +21,7 @@ where
 
 impl<'a, K, V> Extend<(&'a K, &'a V)> for radix_trie::Trie<K, V>
 where
-    K: radix_trie::TrieKey + AsRef<str> + Hash + Eq + Clone,
+    K: radix_trie::TrieKey + Hash + Eq + Clone,
     V: Clone,
 {
     fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: I) {
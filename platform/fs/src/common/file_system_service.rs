@@ -5,7 +5,7 @@ use std::{
     io,
     path::{Path, PathBuf},
     pin::Pin,
-    time::SystemTime,
+    time::{Duration, SystemTime},
 };
 
 #[derive(Copy, Clone)]
@@ -23,6 +23,50 @@ impl Default for CreateOptions {
     }
 }
 
+/// A change on disk yielded by `AbstractFileSystemService::watch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsEvent {
+    Created(PathBuf),
+    Modified(PathBuf),
+    Removed(PathBuf),
+    /// A path renamed within the watched path. A path moved into or out of the watched path
+    /// is reported as `Created` or `Removed`, since the other side of the rename isn't known.
+    Renamed { from: PathBuf, to: PathBuf },
+}
+
+impl FsEvent {
+    pub fn paths(&self) -> Vec<&Path> {
+        match self {
+            FsEvent::Created(path) | FsEvent::Modified(path) | FsEvent::Removed(path) => {
+                vec![path]
+            }
+            FsEvent::Renamed { from, to } => vec![from, to],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// Watches the subdirectories of the watched directory too.
+    pub recursive: bool,
+    /// Changes are yielded once no new change has arrived for `latency`, so a burst of changes,
+    /// e.g. a file written by an editor, is reported once per path.
+    pub latency: Duration,
+    /// Glob patterns, relative to the watched path, of the paths whose changes are not reported,
+    /// e.g. `**/.git`. A path is ignored if the path itself or one of its parent directories matches.
+    pub ignore: Vec<String>,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            recursive: false,
+            latency: Duration::from_millis(100),
+            ignore: Vec::new(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Metadata {
     pub modified: SystemTime,
@@ -49,4 +93,13 @@ pub trait AbstractFileSystemService: Debug + Send + Sync {
 
     async fn is_file(&self, path: &PathBuf) -> bool;
     async fn is_dir(&self, path: &PathBuf) -> bool;
+
+    /// Watches the path and yields its changes, watching stops when the stream is dropped.
+    /// If the watched path is deleted, `FsEvent::Removed` is yielded for it and the changes
+    /// are yielded again from the moment it is created anew, starting with `FsEvent::Created`.
+    async fn watch(
+        &self,
+        path: &PathBuf,
+        options: WatchOptions,
+    ) -> Result<Pin<Box<dyn Send + Stream<Item = FsEvent>>>>;
 }
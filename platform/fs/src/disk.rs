@@ -1 +1,3 @@
 pub mod file_system_service;
+
+mod watcher;
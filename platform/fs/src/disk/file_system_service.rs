@@ -1,13 +1,12 @@
 use anyhow::Result;
 use futures::{AsyncRead, Stream};
 use smol::stream::StreamExt;
-use std::{
-    io,
-    path::{Path, PathBuf},
-    pin::Pin,
-};
+use std::{io, path::PathBuf, pin::Pin};
 
-use crate::common::file_system_service::{AbstractFileSystemService, CreateOptions, Metadata};
+use super::watcher::FsWatcher;
+use crate::common::file_system_service::{
+    AbstractFileSystemService, CreateOptions, FsEvent, Metadata, WatchOptions,
+};
 
 #[async_trait]
 pub trait AbstractDiskFileSystemService: AbstractFileSystemService {
@@ -126,4 +125,12 @@ impl AbstractFileSystemService for DiskFileSystemService {
             .await
             .map_or(false, |metadata| metadata.is_dir())
     }
+
+    async fn watch(
+        &self,
+        path: &PathBuf,
+        options: WatchOptions,
+    ) -> Result<Pin<Box<dyn Send + Stream<Item = FsEvent>>>> {
+        Ok(Box::pin(FsWatcher::new(path, options)?.into_stream()))
+    }
 }
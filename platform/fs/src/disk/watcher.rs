@@ -0,0 +1,530 @@
+use anyhow::{Context as _, Result};
+use futures::{channel::mpsc, Stream, StreamExt};
+use glob::{MatchOptions, Pattern};
+use notify::{
+    event::{MetadataKind, ModifyKind, RenameMode},
+    Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::common::file_system_service::{FsEvent, WatchOptions};
+
+const IGNORE_MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Turns the raw events of `notify` into the `FsEvent`s yielded by `DiskFileSystemService::watch`.
+pub(super) struct FsWatcher {
+    root: PathBuf,
+    recursive_mode: RecursiveMode,
+    latency: Duration,
+    ignore: Vec<Pattern>,
+    watcher: RecommendedWatcher,
+    rx: mpsc::UnboundedReceiver<Event>,
+    /// The closest existing parent directory of the root, watched instead of the root
+    /// while the root doesn't exist, so the watch resumes once the root is created again.
+    watched_ancestor: Option<PathBuf>,
+    /// Events of the last batch that have not been yielded yet.
+    pending: VecDeque<FsEvent>,
+    /// Nothing can be watched anymore, e.g. every parent directory of the root was deleted.
+    closed: bool,
+}
+
+impl FsWatcher {
+    pub(super) fn new(root: &Path, options: WatchOptions) -> Result<Self> {
+        let ignore = options
+            .ignore
+            .iter()
+            .map(|pattern| {
+                Pattern::new(pattern)
+                    .with_context(|| format!("invalid ignore pattern `{}`", pattern))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let (tx, rx) = mpsc::unbounded();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            // A failed event leaves nothing to report, the next changes are reported as usual.
+            if let Ok(event) = event {
+                let _ = tx.unbounded_send(event);
+            }
+        })?;
+
+        let mut this = Self {
+            root: root.to_path_buf(),
+            recursive_mode: if options.recursive {
+                RecursiveMode::Recursive
+            } else {
+                RecursiveMode::NonRecursive
+            },
+            latency: options.latency,
+            ignore,
+            watcher,
+            rx,
+            watched_ancestor: None,
+            pending: VecDeque::new(),
+            closed: false,
+        };
+
+        if this.root.exists() {
+            this.watcher.watch(&this.root, this.recursive_mode)?;
+        } else {
+            this.watch_ancestor()?;
+        }
+
+        Ok(this)
+    }
+
+    pub(super) fn into_stream(self) -> impl Stream<Item = FsEvent> + Send {
+        // The watcher is moved into the stream state, so watching stops when the stream is dropped.
+        futures::stream::unfold(self, |mut this| async move {
+            loop {
+                if let Some(event) = this.pending.pop_front() {
+                    return Some((event, this));
+                }
+                if this.closed {
+                    return None;
+                }
+
+                let events = this.next_batch().await?;
+                let events = this.process(events);
+                this.pending.extend(events);
+            }
+        })
+    }
+
+    /// Waits for the next change and collects the changes that follow until none has arrived for `latency`.
+    async fn next_batch(&mut self) -> Option<Vec<Event>> {
+        let mut events = vec![self.rx.next().await?];
+
+        loop {
+            smol::Timer::after(self.latency).await;
+
+            let mut received = false;
+            while let Ok(Some(event)) = self.rx.try_next() {
+                events.push(event);
+                received = true;
+            }
+
+            if !received {
+                return Some(events);
+            }
+        }
+    }
+
+    fn process(&mut self, events: Vec<Event>) -> Vec<FsEvent> {
+        if self.watched_ancestor.is_some() {
+            // Only the changes of the root are of interest until it exists again.
+            return self.resume_if_created();
+        }
+
+        let is_root_removed = events.iter().any(|event| {
+            matches!(
+                event.kind,
+                EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_))
+            ) && event.paths.contains(&self.root)
+        });
+
+        let mut result: Vec<FsEvent> = coalesce(translate(events, &self.root))
+            .into_iter()
+            .filter_map(|event| self.filter_ignored(event))
+            .collect();
+
+        if self.root.exists() {
+            if is_root_removed {
+                // The root was replaced within the batch, the watch of the removed one is gone.
+                let _ = self.watcher.unwatch(&self.root);
+                if self.watcher.watch(&self.root, self.recursive_mode).is_err() {
+                    self.closed = true;
+                }
+            }
+
+            return result;
+        }
+
+        let root_event = FsEvent::Removed(self.root.clone());
+        if !result.contains(&root_event) {
+            result.push(root_event);
+        }
+
+        let _ = self.watcher.unwatch(&self.root);
+        if self.watch_ancestor().is_err() {
+            self.closed = true;
+        }
+
+        result
+    }
+
+    fn resume_if_created(&mut self) -> Vec<FsEvent> {
+        if !self.root.exists() {
+            // A parent directory closer to the root may have been created in the meantime.
+            if self.watch_ancestor().is_err() {
+                self.closed = true;
+            }
+
+            return Vec::new();
+        }
+
+        if let Some(ancestor) = self.watched_ancestor.take() {
+            let _ = self.watcher.unwatch(&ancestor);
+        }
+        if self.watcher.watch(&self.root, self.recursive_mode).is_err() {
+            self.closed = true;
+            return Vec::new();
+        }
+
+        vec![FsEvent::Created(self.root.clone())]
+    }
+
+    fn watch_ancestor(&mut self) -> Result<()> {
+        let ancestor = self
+            .root
+            .ancestors()
+            .skip(1)
+            .find(|path| path.exists())
+            .ok_or_else(|| anyhow!("no parent directory of {} exists", self.root.display()))?
+            .to_path_buf();
+
+        if self.watched_ancestor.as_ref() == Some(&ancestor) {
+            return Ok(());
+        }
+        if let Some(previous) = self.watched_ancestor.take() {
+            let _ = self.watcher.unwatch(&previous);
+        }
+
+        self.watcher.watch(&ancestor, RecursiveMode::NonRecursive)?;
+        self.watched_ancestor = Some(ancestor);
+
+        Ok(())
+    }
+
+    /// Drops the event if its paths are ignored. A rename between an ignored and a watched path
+    /// is reported as the creation or the removal of the watched path.
+    fn filter_ignored(&self, event: FsEvent) -> Option<FsEvent> {
+        match event {
+            FsEvent::Renamed { from, to } => match (self.is_ignored(&from), self.is_ignored(&to)) {
+                (true, true) => None,
+                (true, false) => Some(FsEvent::Created(to)),
+                (false, true) => Some(FsEvent::Removed(from)),
+                (false, false) => Some(FsEvent::Renamed { from, to }),
+            },
+            event => {
+                let is_ignored = event.paths().into_iter().all(|path| self.is_ignored(path));
+                (!is_ignored).then_some(event)
+            }
+        }
+    }
+
+    fn is_ignored(&self, path: &Path) -> bool {
+        is_ignored(&self.ignore, &self.root, path)
+    }
+}
+
+fn is_ignored(patterns: &[Pattern], root: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+
+    relative
+        .ancestors()
+        .filter(|path| !path.as_os_str().is_empty())
+        .any(|path| {
+            patterns
+                .iter()
+                .any(|pattern| pattern.matches_path_with(path, IGNORE_MATCH_OPTIONS))
+        })
+}
+
+/// Translates the raw events, pairing the two sides of a rename.
+/// Backends report a rename either as a single event with both paths, or as two events
+/// sharing a tracker, some do both.
+fn translate(events: Vec<Event>, root: &Path) -> Vec<FsEvent> {
+    let paired_trackers: HashSet<usize> = events
+        .iter()
+        .filter(|event| event.kind == EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+        .filter_map(|event| event.attrs.tracker())
+        .collect();
+
+    let mut result = Vec::new();
+    let mut rename_from: Option<(Option<usize>, PathBuf)> = None;
+
+    for event in events {
+        let tracker = event.attrs.tracker();
+        let is_paired = tracker.is_some_and(|tracker| paired_trackers.contains(&tracker));
+
+        match event.kind {
+            EventKind::Access(_)
+            | EventKind::Modify(ModifyKind::Metadata(MetadataKind::AccessTime)) => {}
+            EventKind::Create(_) => result.extend(event.paths.into_iter().map(FsEvent::Created)),
+            EventKind::Remove(_) => result.extend(event.paths.into_iter().map(FsEvent::Removed)),
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                let mut paths = event.paths.into_iter();
+                if let (Some(from), Some(to)) = (paths.next(), paths.next()) {
+                    result.push(FsEvent::Renamed { from, to });
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From | RenameMode::To)) if is_paired => {
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                if let Some((_, from)) = rename_from.take() {
+                    result.push(FsEvent::Removed(from));
+                }
+                rename_from = event.paths.into_iter().next().map(|path| (tracker, path));
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                let Some(to) = event.paths.into_iter().next() else {
+                    continue;
+                };
+
+                match rename_from.take() {
+                    Some((from_tracker, from)) if from_tracker == tracker => {
+                        result.push(FsEvent::Renamed { from, to })
+                    }
+                    unpaired => {
+                        if let Some((_, from)) = unpaired {
+                            result.push(FsEvent::Removed(from));
+                        }
+                        result.push(FsEvent::Created(to));
+                    }
+                }
+            }
+            // The backend lost track of the changes, e.g. its queue overflowed.
+            _ if event.need_rescan() => result.push(FsEvent::Modified(root.to_path_buf())),
+            // A rename reported without its direction, or a change of an unknown kind.
+            EventKind::Modify(ModifyKind::Name(_)) | EventKind::Any | EventKind::Other => result
+                .extend(event.paths.into_iter().map(|path| {
+                    if path.exists() {
+                        FsEvent::Modified(path)
+                    } else {
+                        FsEvent::Removed(path)
+                    }
+                })),
+            EventKind::Modify(_) => result.extend(event.paths.into_iter().map(FsEvent::Modified)),
+        }
+    }
+
+    if let Some((_, from)) = rename_from {
+        result.push(FsEvent::Removed(from));
+    }
+
+    result
+}
+
+/// Merges the events of each path into the one that describes the change over the whole batch,
+/// e.g. a file created and then written is reported as created, a file created and removed is not reported.
+fn coalesce(events: Vec<FsEvent>) -> Vec<FsEvent> {
+    let mut result: Vec<Option<FsEvent>> = Vec::new();
+    let mut indices: HashMap<PathBuf, usize> = HashMap::new();
+
+    for event in events {
+        let path = match &event {
+            FsEvent::Created(path) | FsEvent::Modified(path) | FsEvent::Removed(path) => {
+                path.clone()
+            }
+            FsEvent::Renamed { from, to } => {
+                // The events that follow describe the paths after the rename.
+                indices.remove(from);
+                indices.remove(to);
+                result.push(Some(event));
+                continue;
+            }
+        };
+
+        let Some(&index) = indices.get(&path) else {
+            indices.insert(path, result.len());
+            result.push(Some(event));
+            continue;
+        };
+
+        let merged = match (result[index].take(), event) {
+            (Some(FsEvent::Created(path)), FsEvent::Removed(_)) => {
+                indices.remove(&path);
+                None
+            }
+            (Some(FsEvent::Created(path)), _) => Some(FsEvent::Created(path)),
+            (Some(FsEvent::Removed(path)), FsEvent::Removed(_)) => Some(FsEvent::Removed(path)),
+            (Some(_), FsEvent::Removed(path)) => Some(FsEvent::Removed(path)),
+            (_, event) => Some(FsEvent::Modified(event.paths()[0].to_path_buf())),
+        };
+        result[index] = merged;
+    }
+
+    result.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, RemoveKind};
+
+    fn event(kind: EventKind, paths: &[&str]) -> Event {
+        paths.iter().fold(Event::new(kind), |event, path| {
+            event.add_path(PathBuf::from(path))
+        })
+    }
+
+    fn rename(mode: RenameMode, path: &str, tracker: usize) -> Event {
+        event(EventKind::Modify(ModifyKind::Name(mode)), &[path]).set_tracker(tracker)
+    }
+
+    #[test]
+    fn test_translate_pairs_renames() {
+        let root = Path::new("/workspace");
+        let events = vec![
+            // Reported with both a pair of events and a single event, as inotify does.
+            rename(RenameMode::From, "/workspace/a.json", 1),
+            rename(RenameMode::To, "/workspace/b.json", 1),
+            event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                &["/workspace/a.json", "/workspace/b.json"],
+            )
+            .set_tracker(1),
+            // Reported with a pair of events only.
+            rename(RenameMode::From, "/workspace/c.json", 2),
+            rename(RenameMode::To, "/workspace/d.json", 2),
+            // Moved out of the watched directory.
+            rename(RenameMode::From, "/workspace/e.json", 3),
+        ];
+
+        assert_eq!(
+            translate(events, root),
+            vec![
+                FsEvent::Renamed {
+                    from: PathBuf::from("/workspace/a.json"),
+                    to: PathBuf::from("/workspace/b.json"),
+                },
+                FsEvent::Renamed {
+                    from: PathBuf::from("/workspace/c.json"),
+                    to: PathBuf::from("/workspace/d.json"),
+                },
+                FsEvent::Removed(PathBuf::from("/workspace/e.json")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_coalesce() {
+        let root = Path::new("/workspace");
+        let events = vec![
+            event(
+                EventKind::Create(CreateKind::File),
+                &["/workspace/new.json"],
+            ),
+            event(
+                EventKind::Modify(ModifyKind::Data(DataChange::Any)),
+                &["/workspace/new.json"],
+            ),
+            event(
+                EventKind::Create(CreateKind::File),
+                &["/workspace/tmp.json"],
+            ),
+            event(
+                EventKind::Remove(RemoveKind::File),
+                &["/workspace/tmp.json"],
+            ),
+            event(
+                EventKind::Remove(RemoveKind::File),
+                &["/workspace/settings.json"],
+            ),
+            event(
+                EventKind::Create(CreateKind::File),
+                &["/workspace/settings.json"],
+            ),
+            event(
+                EventKind::Modify(ModifyKind::Data(DataChange::Any)),
+                &["/workspace/settings.json"],
+            ),
+        ];
+
+        assert_eq!(
+            coalesce(translate(events, root)),
+            vec![
+                FsEvent::Created(PathBuf::from("/workspace/new.json")),
+                FsEvent::Modified(PathBuf::from("/workspace/settings.json")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_ignored() {
+        let root = Path::new("/workspace");
+        let patterns = vec![
+            Pattern::new("**/.git").unwrap(),
+            Pattern::new("*.log").unwrap(),
+        ];
+
+        assert!(is_ignored(&patterns, root, Path::new("/workspace/.git")));
+        assert!(is_ignored(
+            &patterns,
+            root,
+            Path::new("/workspace/sub/.git/index")
+        ));
+        assert!(is_ignored(
+            &patterns,
+            root,
+            Path::new("/workspace/debug.log")
+        ));
+        assert!(!is_ignored(
+            &patterns,
+            root,
+            Path::new("/workspace/logs/debug.log")
+        ));
+        assert!(!is_ignored(&patterns, root, Path::new("/workspace")));
+    }
+
+    #[test]
+    fn test_watch_deleted_directory() {
+        let dir = std::env::temp_dir().join(format!("moss_fs_watch_{}", std::process::id()));
+        let root = dir.join("settings");
+        std::fs::create_dir_all(&root).unwrap();
+
+        let options = WatchOptions {
+            recursive: true,
+            latency: Duration::from_millis(50),
+            ignore: vec!["**/*.tmp".to_string()],
+        };
+        let mut events = Box::pin(FsWatcher::new(&root, options).unwrap().into_stream());
+        let mut next_event = || {
+            futures::executor::block_on(async {
+                let timeout = smol::Timer::after(Duration::from_secs(5));
+                match futures::future::select(events.next(), timeout).await {
+                    futures::future::Either::Left((event, _)) => event,
+                    futures::future::Either::Right(_) => None,
+                }
+            })
+        };
+
+        std::fs::write(root.join("settings.tmp"), "{}").unwrap();
+        std::fs::rename(root.join("settings.tmp"), root.join("settings.json")).unwrap();
+        assert_eq!(
+            next_event(),
+            Some(FsEvent::Created(root.join("settings.json")))
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+        let mut removed = Vec::new();
+        while let Some(event) = next_event() {
+            removed.push(event);
+            if removed.contains(&FsEvent::Removed(root.clone())) {
+                break;
+            }
+        }
+        assert!(removed.contains(&FsEvent::Removed(root.clone())));
+
+        std::fs::create_dir_all(&root).unwrap();
+        assert_eq!(next_event(), Some(FsEvent::Created(root.clone())));
+
+        std::fs::write(root.join("settings.json"), "{}").unwrap();
+        assert_eq!(
+            next_event(),
+            Some(FsEvent::Created(root.join("settings.json")))
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -6,9 +6,26 @@ pub enum WorkspaceId {
     Some(String),
 }
 
+/// Whether the user trusts the content of the workspace folders.
+/// Untrusted workspaces are opened in restricted mode, in which their settings
+/// can't change the settings that are only allowed for trusted sources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WorkspaceTrust {
+    Trusted,
+    #[default]
+    Untrusted,
+}
+
+impl WorkspaceTrust {
+    pub fn is_trusted(&self) -> bool {
+        *self == WorkspaceTrust::Trusted
+    }
+}
+
 #[derive(Debug)]
 pub struct Workspace {
     pub id: WorkspaceId,
     pub folders: Vec<String>,
     pub configuration_uri: Option<PathBuf>,
+    pub trust: WorkspaceTrust,
 }
@@ -26,6 +26,7 @@ struct Args {
 #[derive(Subcommand)]
 enum CliCommand {
     License(tasks::license::LicenseCommandArgs),
+    Config(tasks::configuration::ConfigCommandArgs),
     Rwa(tasks::rust_workspace_audit::RustWorkspaceAuditCommandArgs),
 }
 
@@ -39,19 +40,23 @@ async fn main() -> Result<()> {
     tracing::subscriber::set_global_default(subscriber)
         .context("setting default subscriber failed")?;
 
-    let metadata = load_cargo_metadata()?;
     let mut runner = TaskRunner::new();
 
     match args.command {
         CliCommand::License(args) => {
+            let metadata = load_cargo_metadata()?;
             runner.spawn_job(tasks::license::run_license(args, metadata));
             runner.run().await
         }
         CliCommand::Rwa(args) => {
+            let metadata = load_cargo_metadata()?;
             runner.spawn_job(tasks::rust_workspace_audit::check_dependencies_job(
                 args, metadata,
             ));
             runner.run().await
         }
+        CliCommand::Config(args) => {
+            tokio::task::spawn_blocking(move || tasks::configuration::run_config(args)).await?
+        }
     }
 }
@@ -1,3 +1,4 @@
+pub mod configuration;
 pub mod license;
 pub mod rust_workspace_audit;
 
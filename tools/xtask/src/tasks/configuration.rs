@@ -0,0 +1,334 @@
+use anyhow::{Context as _, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use platform_configuration::{
+    configuration_bundle::{ConfigurationBundle, ExportOptions, ExportSource},
+    configuration_contribution::ConfigurationContributions,
+    configuration_default::DefaultConfiguration,
+    configuration_diagnostic::{ConfigurationDiagnostic, DiagnosticSeverity},
+    configuration_key::ConfigurationKey,
+    configuration_model::{Configuration, ConfigurationModel, ConfigurationTarget},
+    configuration_parser::ConfigurationParser,
+    configuration_registry::ConfigurationRegistry,
+};
+use platform_core::context_v2::{atom::Atom, AnyContext, Context, ContextCell};
+use platform_core::platform::cross::client::CrossPlatformClient;
+use serde_json::Value;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+};
+
+#[derive(Parser)]
+pub struct ConfigCommandArgs {
+    #[command(subcommand)]
+    command: ConfigCommand,
+    /// Directory with configuration contribution manifests to register next to the workbench settings.
+    #[clap(long, global = true)]
+    contributions: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Validates a settings file against the settings registered by the workbench.
+    Validate {
+        path: PathBuf,
+        #[clap(long, value_enum, default_value = "user")]
+        target: SettingsTarget,
+        /// Fails on warnings too, e.g. unknown settings.
+        #[clap(long)]
+        strict: bool,
+    },
+    /// Prints the effective configuration after merging the settings files with the defaults.
+    Dump {
+        #[clap(long)]
+        user: Option<PathBuf>,
+        #[clap(long)]
+        workspace: Option<PathBuf>,
+        /// Prints the values of the settings marked as secret.
+        #[clap(long)]
+        include_secrets: bool,
+    },
+    /// Prints the settings that differ between two settings files.
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        #[clap(long, value_enum, default_value = "user")]
+        target: SettingsTarget,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SettingsTarget {
+    User,
+    Workspace,
+    Folder,
+}
+
+impl From<SettingsTarget> for ConfigurationTarget {
+    fn from(target: SettingsTarget) -> Self {
+        match target {
+            SettingsTarget::User => ConfigurationTarget::User,
+            SettingsTarget::Workspace => ConfigurationTarget::Workspace,
+            SettingsTarget::Folder => ConfigurationTarget::WorkspaceFolder,
+        }
+    }
+}
+
+/// Must run outside of the async runtime, since the configuration context starts a runtime of its own.
+/// Returns an error if a settings file has errors, so the command exits with a non-zero code.
+pub fn run_config(args: ConfigCommandArgs) -> Result<()> {
+    let ctx_cell = ContextCell::new(Rc::new(CrossPlatformClient::new()));
+    let ctx: &mut Context = &mut ctx_cell.borrow_mut();
+
+    let contributions = match &args.contributions {
+        Some(dir) => ConfigurationContributions::load(dir)?,
+        None => ConfigurationContributions::default(),
+    };
+
+    let mut registry = ConfigurationRegistry::new();
+    for node in workbench_contribution::configuration_nodes() {
+        registry.register_configuration(node);
+    }
+
+    let mut diagnostics = contributions.diagnostics;
+    for node in &contributions.nodes {
+        diagnostics.extend(registry.register_contribution(node));
+    }
+    check_diagnostics(&diagnostics, false)?;
+
+    let registry = ctx.create_atom(|_| registry);
+
+    match args.command {
+        ConfigCommand::Validate {
+            path,
+            target,
+            strict,
+        } => {
+            let model = load_settings(ctx, &registry, &path, target)?;
+            check_diagnostics(model.diagnostics(), strict)
+        }
+        ConfigCommand::Dump {
+            user,
+            workspace,
+            include_secrets,
+        } => {
+            let user_model = load_optional_settings(ctx, &registry, user, SettingsTarget::User)?;
+            let workspace_model =
+                load_optional_settings(ctx, &registry, workspace, SettingsTarget::Workspace)?;
+
+            let default_configuration = DefaultConfiguration::new(registry.clone());
+            default_configuration.initialize(ctx);
+            let default_model = default_configuration
+                .get_configuration_model()
+                .context("failed to load the default configuration")?;
+
+            let configuration = Configuration::new(
+                default_model,
+                Arc::new(ConfigurationModel::empty()),
+                user_model,
+                workspace_model,
+                Arc::new(ConfigurationModel::empty()),
+            );
+            let bundle = ConfigurationBundle::export(
+                &configuration,
+                registry.read(ctx),
+                &ExportOptions {
+                    source: ExportSource::Effective,
+                    strip_defaults: false,
+                    include_secrets,
+                },
+            );
+
+            println!("{}", serde_json::to_string_pretty(&bundle.settings)?);
+            Ok(())
+        }
+        ConfigCommand::Diff { old, new, target } => {
+            let old_model = load_settings(ctx, &registry, &old, target)?;
+            check_diagnostics(old_model.diagnostics(), false)?;
+            let new_model = load_settings(ctx, &registry, &new, target)?;
+            check_diagnostics(new_model.diagnostics(), false)?;
+
+            for line in diff_lines(Arc::new(old_model), Arc::new(new_model)) {
+                println!("{line}");
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Formats the settings that differ between the models as `<key>: <change>` lines, sorted by key.
+fn diff_lines(
+    old_model: Arc<ConfigurationModel>,
+    new_model: Arc<ConfigurationModel>,
+) -> Vec<String> {
+    let difference = Configuration::compare(Arc::clone(&old_model), Arc::clone(&new_model));
+
+    let value = |model: &ConfigurationModel, key: &ConfigurationKey| {
+        model.get(key).map(Value::to_string).unwrap_or_default()
+    };
+
+    let mut lines = Vec::new();
+    for key in &difference.added {
+        lines.push((display_key(key), format!("+ {}", value(&new_model, key))));
+    }
+    for key in &difference.removed {
+        lines.push((display_key(key), format!("- {}", value(&old_model, key))));
+    }
+    for key in &difference.modified {
+        lines.push((
+            display_key(key),
+            format!("~ {} -> {}", value(&old_model, key), value(&new_model, key)),
+        ));
+    }
+    lines.sort();
+
+    lines
+        .into_iter()
+        .map(|(key, change)| format!("{key}: {change}"))
+        .collect()
+}
+
+fn load_settings(
+    ctx: &mut Context,
+    registry: &Atom<ConfigurationRegistry>,
+    path: &Path,
+    target: SettingsTarget,
+) -> Result<ConfigurationModel> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read settings file {}", path.display()))?;
+    let parser = ConfigurationParser::new(registry.clone()).with_target(target.into());
+
+    let mut model = parser.parse(ctx, &content);
+    model.set_diagnostics_source(path);
+
+    Ok(model)
+}
+
+fn load_optional_settings(
+    ctx: &mut Context,
+    registry: &Atom<ConfigurationRegistry>,
+    path: Option<PathBuf>,
+    target: SettingsTarget,
+) -> Result<Arc<ConfigurationModel>> {
+    let Some(path) = path else {
+        return Ok(Arc::new(ConfigurationModel::empty()));
+    };
+
+    let model = load_settings(ctx, registry, &path, target)?;
+    check_diagnostics(model.diagnostics(), false)?;
+
+    Ok(Arc::new(model))
+}
+
+fn check_diagnostics(diagnostics: &[ConfigurationDiagnostic], strict: bool) -> Result<()> {
+    let mut failures = 0;
+    for diagnostic in diagnostics {
+        eprintln!("{diagnostic}");
+
+        match diagnostic.severity {
+            DiagnosticSeverity::Error => failures += 1,
+            DiagnosticSeverity::Warning if strict => failures += 1,
+            DiagnosticSeverity::Warning | DiagnosticSeverity::Info => {}
+        }
+    }
+
+    if failures > 0 {
+        return Err(anyhow!(
+            "settings validation failed with {failures} problem(s)"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Formats `[rust].editor.tabSize` as `[rust] editor.tabSize`.
+fn display_key(key: &ConfigurationKey) -> String {
+    key.json_path().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_registry(ctx: &mut Context) -> Atom<ConfigurationRegistry> {
+        ctx.create_atom(|_| {
+            let mut registry = ConfigurationRegistry::new();
+            for node in workbench_contribution::configuration_nodes() {
+                registry.register_configuration(node);
+            }
+
+            registry
+        })
+    }
+
+    fn write_settings(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn test_check_diagnostics() {
+        let warnings = vec![
+            ConfigurationDiagnostic::warning("Unknown configuration setting `window.unknown`"),
+            ConfigurationDiagnostic::info("Setting `window.size` was migrated"),
+        ];
+        assert!(check_diagnostics(&warnings, false).is_ok());
+        assert_eq!(
+            check_diagnostics(&warnings, true).unwrap_err().to_string(),
+            "settings validation failed with 1 problem(s)"
+        );
+
+        let errors = vec![
+            ConfigurationDiagnostic::error("Expected a value"),
+            ConfigurationDiagnostic::warning("Unknown configuration setting `window.unknown`"),
+        ];
+        assert_eq!(
+            check_diagnostics(&errors, false).unwrap_err().to_string(),
+            "settings validation failed with 1 problem(s)"
+        );
+        assert_eq!(
+            check_diagnostics(&errors, true).unwrap_err().to_string(),
+            "settings validation failed with 2 problem(s)"
+        );
+
+        assert!(check_diagnostics(&[], true).is_ok());
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let dir = std::env::temp_dir().join(format!("moss_xtask_config_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let old = write_settings(
+            &dir,
+            "old.json",
+            r#"{ "window.defaultWidth": 1200, "window.restoreTab": false }"#,
+        );
+        let new = write_settings(
+            &dir,
+            "new.json",
+            r#"{ "window.defaultWidth": 1600, "window.restoreFullScreen": false }"#,
+        );
+
+        let ctx_cell = ContextCell::new(Rc::new(CrossPlatformClient::new()));
+        let ctx: &mut Context = &mut ctx_cell.borrow_mut();
+        let registry = create_registry(ctx);
+        let old_model = load_settings(ctx, &registry, &old, SettingsTarget::User).unwrap();
+        let new_model = load_settings(ctx, &registry, &new, SettingsTarget::User).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(old_model.diagnostics().is_empty());
+        assert!(new_model.diagnostics().is_empty());
+        assert_eq!(
+            diff_lines(Arc::new(old_model), Arc::new(new_model)),
+            vec![
+                "window.defaultWidth: ~ 1200 -> 1600",
+                "window.restoreFullScreen: + false",
+                "window.restoreTab: - false",
+            ]
+        );
+    }
+}
@@ -1,2 +1,3 @@
 pub mod cmd_base;
+pub mod cmd_configuration;
 pub mod cmd_dummy;
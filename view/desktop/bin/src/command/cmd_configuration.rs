@@ -0,0 +1,53 @@
+use platform_configuration::configuration_search::SettingSearchResult;
+use platform_core::context_v2::async_context::AsyncContext;
+use specta::Type;
+use tauri::State;
+
+use crate::AppState;
+
+#[derive(Debug, Type, Serialize)]
+pub struct SettingSearchResultDTO {
+    pub key: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub deprecated: bool,
+    pub enum_item_labels: Option<Vec<String>>,
+    pub value: Option<serde_json::Value>,
+    pub default_value: Option<serde_json::Value>,
+    pub is_modified: bool,
+}
+
+impl From<SettingSearchResult> for SettingSearchResultDTO {
+    fn from(value: SettingSearchResult) -> Self {
+        Self {
+            key: value.key,
+            title: value.title,
+            description: value.description,
+            tags: value.tags,
+            deprecated: value.deprecated,
+            enum_item_labels: value.enum_item_labels,
+            value: value.value,
+            default_value: value.default_value,
+            is_modified: value.is_modified,
+        }
+    }
+}
+
+#[tauri::command(async)]
+#[specta::specta]
+pub async fn search_settings(
+    async_ctx: State<'_, AsyncContext>,
+    state: State<'_, AppState>,
+    query: String,
+) -> Result<Vec<SettingSearchResultDTO>, String> {
+    let results = state
+        .workbench
+        .search_settings(async_ctx.inner(), &query)
+        .map_err(|e| e.to_string())?;
+
+    Ok(results
+        .into_iter()
+        .map(SettingSearchResultDTO::from)
+        .collect())
+}
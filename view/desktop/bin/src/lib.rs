@@ -25,7 +25,7 @@ use workbench_service_environment_tao::environment_service::NativeEnvironmentSer
 use workbench_tao::window::{NativePlatformInfo, NativeWindowConfiguration};
 use workbench_tao::Workbench;
 
-use crate::command::{cmd_base, cmd_dummy};
+use crate::command::{cmd_base, cmd_configuration, cmd_dummy};
 
 #[macro_use]
 extern crate serde;
@@ -114,7 +114,12 @@ fn setup_app(
         .get_unchecked::<MockStorageService>()
         .get_last_window_state();
 
-    let workbench = Workbench::new(&mut ctx, service_group, window_state.workspace_id)?;
+    let workbench = Workbench::new(
+        &mut ctx,
+        service_group,
+        window_state.workspace_id,
+        &platform_info,
+    )?;
     workbench.initialize(&mut ctx)?;
 
     let window = app.get_webview_window("main").unwrap();
@@ -156,6 +161,7 @@ fn create_specta_builder() -> Builder {
             cmd_dummy::fetch_all_themes,
             cmd_dummy::read_theme,
             cmd_base::native_platform_info,
+            cmd_configuration::search_settings,
         ])
 }
 
@@ -58,6 +58,14 @@ export const commands = {
   async nativePlatformInfo(): Promise<NativePlatformInfo> {
     return await TAURI_INVOKE("native_platform_info");
   },
+  async searchSettings(query: string): Promise<Result<SettingSearchResultDTO[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("search_settings", { query }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
 };
 
 /** user-defined events **/
@@ -67,10 +75,22 @@ export const commands = {
 /** user-defined types **/
 
 export type CreateProjectInput = { source: string; repository: string | null };
+export type JsonValue = null | boolean | number | string | JsonValue[] | { [key in string]: JsonValue };
 export type NativePlatformInfo = { os: string; version: string; hostname: string };
 export type ProjectDTO = { id: string; source: string; repository: string | null; created_at: string };
 export type SessionDTO = { id: string };
 export type SessionInfoDTO = { created_at: string; project: ProjectDTO; session: SessionDTO };
+export type SettingSearchResultDTO = {
+  key: string;
+  title: string;
+  description: string | null;
+  tags: string[];
+  deprecated: boolean;
+  enum_item_labels: string[] | null;
+  value: JsonValue | null;
+  default_value: JsonValue | null;
+  is_modified: boolean;
+};
 export type WorkbenchState = "Empty" | "Workspace";
 
 /** tauri-specta globals **/
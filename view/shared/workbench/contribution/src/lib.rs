@@ -1,10 +1,41 @@
+#[macro_use]
+extern crate lazy_static;
+
 use platform_configuration::{
+    configuration_platform::ConfigurationPlatform,
     configuration_registry::{
         ConfigurationNode, ConfigurationNodeType as Type,
         ConfigurationPropertySchema as PropertySchema, PropertyMap,
     },
     property_key,
+    typed_settings::{SettingKey, Settings},
 };
+use serde::Deserialize;
+
+pub const WINDOW_RESTORE_FULL_SCREEN: SettingKey<bool> =
+    SettingKey::new("window.restoreFullScreen");
+pub const WINDOW_RESTORE_TAB: SettingKey<bool> = SettingKey::new("window.restoreTab");
+pub const WINDOW_DEFAULT_WIDTH: SettingKey<u32> = SettingKey::new("window.defaultWidth");
+pub const WINDOW_DEFAULT_HEIGHT: SettingKey<u32> = SettingKey::new("window.defaultHeight");
+
+/// All the configuration nodes the workbench registers, shared with tools that validate settings files.
+pub fn configuration_nodes() -> Vec<&'static ConfigurationNode> {
+    vec![&WORKBENCH_TAO_WINDOW]
+}
+
+/// Settings registered by `WORKBENCH_TAO_WINDOW`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowSettings {
+    pub default_width: u32,
+    pub default_height: u32,
+}
+
+impl Settings for WindowSettings {
+    fn node() -> &'static ConfigurationNode {
+        &WORKBENCH_TAO_WINDOW
+    }
+}
 
 lazy_static! {
     pub static ref WORKBENCH_TAO_WINDOW: ConfigurationNode = ConfigurationNode {
@@ -21,7 +52,7 @@ lazy_static! {
             properties.insert(
                 property_key!(window.restoreFullScreen),
                 PropertySchema {
-                    typ: Some(Type::Number),
+                    typ: Some(Type::Bool),
                     default: Some(serde_json::Value::Bool(true)),
                     description: Some("Determines whether the window should be restored in full-screen mode on the next launch".to_string()),
                     ..Default::default()
@@ -30,7 +61,7 @@ lazy_static! {
             properties.insert(
                 property_key!(window.restoreTab),
                 PropertySchema {
-                    typ: Some(Type::Number),
+                    typ: Some(Type::Bool),
                     default: Some(serde_json::Value::Bool(true)),
                     description: Some("Determines whether the window should restore the last opened tab on the next launch".to_string()),
                     ..Default::default()
@@ -41,6 +72,15 @@ lazy_static! {
                 PropertySchema {
                     typ: Some(Type::Number),
                     default: Some(serde_json::Value::Number(serde_json::Number::from(1400))),
+                    // Most macOS devices are laptops with smaller screens.
+                    platform_defaults: Some(
+                        [(
+                            ConfigurationPlatform::MacOS,
+                            serde_json::Value::Number(serde_json::Number::from(1280)),
+                        )]
+                        .into_iter()
+                        .collect(),
+                    ),
                     description: Some("The default window width in logical pixels".to_string()),
                     ..Default::default()
                 },
@@ -87,3 +127,18 @@ lazy_static! {
         parent_of: None,
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setting_keys_are_registered() {
+        WINDOW_RESTORE_FULL_SCREEN
+            .check(&WORKBENCH_TAO_WINDOW)
+            .unwrap();
+        WINDOW_RESTORE_TAB.check(&WORKBENCH_TAO_WINDOW).unwrap();
+        WINDOW_DEFAULT_WIDTH.check(&WORKBENCH_TAO_WINDOW).unwrap();
+        WINDOW_DEFAULT_HEIGHT.check(&WORKBENCH_TAO_WINDOW).unwrap();
+    }
+}
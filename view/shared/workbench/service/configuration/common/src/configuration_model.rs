@@ -1,15 +1,18 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
+use platform_configuration::configuration_bundle::{ConfigurationBundle, ExportOptions};
+use platform_configuration::configuration_diagnostic::ConfigurationDiagnostic;
+use platform_configuration::configuration_key::ConfigurationKey;
 use platform_configuration::configuration_model::{
-    AttributeName, Configuration as PlatformConfiguration, ConfigurationModel,
+    Configuration as PlatformConfiguration, ConfigurationDifference, ConfigurationModel,
+    ConfigurationOverrides, InspectedConfigurationValue,
+};
+use platform_configuration::configuration_registry::ConfigurationRegistry;
+use platform_configuration::configuration_search::{
+    search_settings, SettingSearchResult, SettingsQuery,
 };
-use platform_configuration::configuration_parser::ConfigurationParser as PlatformConfigurationParser;
 use serde_json::Value;
 
-pub struct WorkspaceConfigurationParser {
-    platform_parser: PlatformConfigurationParser,
-}
-
 pub struct WorkspaceConfiguration {
     platform_configuration: PlatformConfiguration,
 }
@@ -33,7 +36,114 @@ impl WorkspaceConfiguration {
         }
     }
 
-    pub fn get_value(&self, attribute_name: &AttributeName) -> Option<Value> {
+    pub fn get_value(&self, attribute_name: &ConfigurationKey) -> Option<Value> {
         self.platform_configuration.get_value(attribute_name)
     }
+
+    pub fn with_workspace_root(mut self, workspace_root: Option<PathBuf>) -> Self {
+        self.platform_configuration = self
+            .platform_configuration
+            .with_workspace_root(workspace_root);
+        self
+    }
+
+    pub fn get_value_with_overrides(
+        &self,
+        attribute_name: &ConfigurationKey,
+        overrides: &ConfigurationOverrides,
+    ) -> Option<Value> {
+        self.platform_configuration
+            .get_value_with_overrides(attribute_name, overrides)
+    }
+
+    pub fn get_value_for_override(
+        &self,
+        attribute_name: &ConfigurationKey,
+        override_ident: Option<&str>,
+    ) -> Option<Value> {
+        self.platform_configuration
+            .get_value_for_override(attribute_name, override_ident)
+    }
+
+    pub fn get_section(&self, section: &ConfigurationKey) -> Option<Value> {
+        self.platform_configuration.get_section(section)
+    }
+
+    pub fn diagnostics(&self) -> Vec<ConfigurationDiagnostic> {
+        self.platform_configuration.diagnostics()
+    }
+
+    pub fn get_user_configuration(&self) -> Arc<ConfigurationModel> {
+        self.platform_configuration.get_user_configuration()
+    }
+
+    pub fn get_workspace_configuration(&self) -> Arc<ConfigurationModel> {
+        self.platform_configuration.get_workspace_configuration()
+    }
+
+    pub fn export(
+        &self,
+        registry: &ConfigurationRegistry,
+        options: &ExportOptions,
+    ) -> ConfigurationBundle {
+        ConfigurationBundle::export(&self.platform_configuration, registry, options)
+    }
+
+    pub fn search(
+        &self,
+        registry: &ConfigurationRegistry,
+        query: &SettingsQuery,
+    ) -> Vec<SettingSearchResult> {
+        search_settings(registry, &self.platform_configuration, query)
+    }
+
+    pub fn inspect(&self, attribute_name: &ConfigurationKey) -> InspectedConfigurationValue {
+        self.platform_configuration.inspect(attribute_name)
+    }
+
+    pub fn update_workspace_configuration(
+        &self,
+        workspace_configuration: Arc<ConfigurationModel>,
+    ) -> ConfigurationDifference {
+        self.platform_configuration
+            .update_workspace_configuration(workspace_configuration)
+    }
+
+    pub fn set_memory_value(
+        &self,
+        attribute_name: &ConfigurationKey,
+        value: Option<Value>,
+    ) -> ConfigurationDifference {
+        self.platform_configuration
+            .set_memory_value(attribute_name, value)
+    }
+
+    pub fn clear_memory_configuration(&self) -> ConfigurationDifference {
+        self.platform_configuration.clear_memory_configuration()
+    }
+
+    pub fn update_folder_configuration(
+        &self,
+        folder: PathBuf,
+        folder_configuration: Arc<ConfigurationModel>,
+    ) -> ConfigurationDifference {
+        self.platform_configuration
+            .update_folder_configuration(folder, folder_configuration)
+    }
+
+    pub fn update_policy_configuration(
+        &self,
+        policy_configuration: Arc<ConfigurationModel>,
+    ) -> ConfigurationDifference {
+        self.platform_configuration
+            .update_policy_configuration(policy_configuration)
+    }
+
+    pub fn update_user_configuration(
+        &self,
+        user_configuration: Arc<ConfigurationModel>,
+    ) -> ConfigurationDifference {
+        self.platform_configuration
+            .update_user_configuration(user_configuration)
+    }
 }
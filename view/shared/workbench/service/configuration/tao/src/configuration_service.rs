@@ -1,81 +1,877 @@
 use anyhow::Context as ResultContext;
 use anyhow::Result;
+use futures::Stream;
+use platform_configuration::configuration_bundle::{
+    ConfigurationBundle, ConflictResolution, ExportOptions, ImportConflict, ImportPreview,
+};
+use platform_configuration::configuration_diagnostic::ConfigurationDiagnostic;
+use platform_configuration::configuration_editing::ConfigurationEditingService;
+use platform_configuration::configuration_error::ConfigurationError;
+use platform_configuration::configuration_migration::MigratedSetting;
 use platform_configuration::configuration_parser::ConfigurationParser;
+use platform_configuration::configuration_search::{SettingSearchResult, SettingsQuery};
 use platform_configuration::user_settings::UserSettings;
 use platform_configuration::{
     configuration_default::DefaultConfiguration,
-    configuration_model::{AttributeName, ConfigurationModel},
-    configuration_policy::{ConfigurationPolicy, ConfigurationPolicyService},
+    configuration_key::ConfigurationKey,
+    configuration_model::{
+        ConfigurationChangeEvent, ConfigurationDifference, ConfigurationModel,
+        ConfigurationOverrides, ConfigurationTarget,
+    },
+    configuration_platform::ConfigurationPlatform,
+    configuration_policy::ConfigurationPolicy,
     configuration_registry::ConfigurationRegistry,
+    policy_file::FilePolicyProvider,
     AbstractConfigurationService,
 };
 use platform_core::context_v2::atom::Atom;
 use platform_core::context_v2::Context;
 use platform_fs::disk::file_system_service::AbstractDiskFileSystemService;
-use platform_workspace::Workspace;
-use std::{path::PathBuf, sync::Arc};
+use platform_workspace::{Workspace, WorkspaceTrust};
+use std::{
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use workbench_service_configuration_common::configuration_model::WorkspaceConfiguration;
 
 pub struct WorkspaceConfigurationService {
     workspace: Workspace,
+    registry: Atom<ConfigurationRegistry>,
     configuration: WorkspaceConfiguration,
+    default_configuration: DefaultConfiguration,
+    configuration_policy: ConfigurationPolicy,
+    policy_provider: FilePolicyProvider,
+    user_configuration: UserSettings,
+    workspace_configuration: Option<UserSettings>,
+    folder_configurations: Vec<(PathBuf, UserSettings)>,
+    /// Parsers of the workspace and workspace folder settings files,
+    /// they run in restricted mode while the workspace isn't trusted.
+    workspace_parsers: Vec<Arc<ConfigurationParser>>,
+    workspace_trusted: AtomicBool,
+    configuration_editing: ConfigurationEditingService,
 }
 
 impl WorkspaceConfigurationService {
+    /// Loads the settings and the policy files. Missing files are read as empty, but a file
+    /// that exists and can't be read, e.g. because of its permissions, is an error.
     pub fn new(
         ctx: &mut Context,
         workspace: Workspace,
         registry: Atom<ConfigurationRegistry>,
-        policy_service: ConfigurationPolicyService,
+        platform: ConfigurationPlatform,
+        policy_provider: FilePolicyProvider,
         user_configuration_resource: PathBuf,
         fs_service: Arc<dyn AbstractDiskFileSystemService>,
-    ) -> Self {
-        let parser = ConfigurationParser::new(registry.clone()); // TODO: platform ConfigurationParser?
+    ) -> Result<Self> {
+        // TODO: platform ConfigurationParser?
+        let parser =
+            ConfigurationParser::new(registry.clone()).with_target(ConfigurationTarget::User);
 
-        let default_configuration = DefaultConfiguration::new(registry.clone()); // TODO: use WorkspaceDefaultConfiguration
+        let default_configuration =
+            DefaultConfiguration::new(registry.clone()).with_platform(platform); // TODO: use WorkspaceDefaultConfiguration
         default_configuration.initialize(ctx);
 
-        let user_configuration =
-            UserSettings::new(user_configuration_resource, Arc::new(parser), fs_service);
+        let configuration_editing =
+            ConfigurationEditingService::new(user_configuration_resource.clone());
+
+        let user_configuration = UserSettings::new(
+            user_configuration_resource,
+            Arc::new(parser),
+            Arc::clone(&fs_service),
+        );
         let user_configuration_model = user_configuration
             .load_configuration(ctx)
-            .context("failed to load user configuration model")
-            .unwrap();
+            .context("failed to load user configuration model")?;
+
+        let restricted_mode = !workspace.trust.is_trusted();
+        let mut workspace_parsers = Vec::new();
+
+        let workspace_configuration = workspace.configuration_uri.clone().map(|resource| {
+            let parser = Arc::new(
+                ConfigurationParser::new(registry.clone())
+                    .with_target(ConfigurationTarget::Workspace)
+                    .with_restricted_mode(restricted_mode),
+            );
+            workspace_parsers.push(Arc::clone(&parser));
+
+            UserSettings::new(resource, parser, Arc::clone(&fs_service))
+        });
+        let workspace_configuration_model = workspace_configuration
+            .as_ref()
+            .map(|workspace_configuration| {
+                workspace_configuration
+                    .load_configuration(ctx)
+                    .context("failed to load workspace configuration model")
+            })
+            .transpose()?
+            .unwrap_or_else(ConfigurationModel::empty);
         let default_configuration_model = default_configuration
             .get_configuration_model()
             .context("failed to get default configuration model".to_string())
-            .context("default was not initialized correctly")
-            .unwrap();
+            .context("default was not initialized correctly")?;
+
+        // Every workspace folder may have its own settings file in `<folder>/.moss/settings.json`.
+        let folder_configurations = workspace
+            .folders
+            .iter()
+            .map(|folder| {
+                let folder = PathBuf::from(folder);
+                let folder = std::path::absolute(&folder).unwrap_or(folder);
+                let parser = Arc::new(
+                    ConfigurationParser::new(registry.clone())
+                        .with_target(ConfigurationTarget::WorkspaceFolder)
+                        .with_restricted_mode(restricted_mode),
+                );
+                workspace_parsers.push(Arc::clone(&parser));
+                let resource = folder.join(".moss").join("settings.json");
 
-        let mut configuration_policy = ConfigurationPolicy::new(registry.clone(), policy_service);
+                (
+                    folder,
+                    UserSettings::new(resource, parser, Arc::clone(&fs_service)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // Problems in the content of the policy file are reported through the policy model diagnostics.
+        let policy_service = policy_provider
+            .load(ctx)
+            .context("failed to load policies")?;
+        let configuration_policy = ConfigurationPolicy::new(registry.clone(), policy_service);
         configuration_policy.initialize(ctx, &default_configuration);
 
         let policy_configuration_model = configuration_policy.get_model();
 
-        Self {
+        // The workspace settings file is stored in `<root>/.moss/settings.json`.
+        let workspace_root = workspace
+            .configuration_uri
+            .as_ref()
+            .and_then(|resource| resource.parent())
+            .and_then(|resource| resource.parent())
+            .and_then(|root| std::path::absolute(root).ok());
+
+        let configuration = WorkspaceConfiguration::new(
+            default_configuration_model,
+            policy_configuration_model,
+            Arc::new(user_configuration_model),
+            Arc::new(workspace_configuration_model),
+            Arc::new(ConfigurationModel::empty()),
+        )
+        .with_workspace_root(workspace_root);
+
+        for (folder, folder_configuration) in &folder_configurations {
+            let folder_configuration_model = folder_configuration
+                .load_configuration(ctx)
+                .context("failed to load workspace folder configuration model")?;
+            configuration
+                .update_folder_configuration(folder.clone(), Arc::new(folder_configuration_model));
+        }
+
+        Ok(Self {
             workspace,
-            configuration: WorkspaceConfiguration::new(
-                default_configuration_model,
-                policy_configuration_model,
-                Arc::new(user_configuration_model),
-                Arc::new(ConfigurationModel::empty()),
-                Arc::new(ConfigurationModel::empty()),
-            ),
+            registry,
+            configuration,
+            default_configuration,
+            configuration_policy,
+            policy_provider,
+            user_configuration,
+            workspace_configuration,
+            folder_configurations,
+            workspace_parsers,
+            workspace_trusted: AtomicBool::new(!restricted_mode),
+            configuration_editing,
+        })
+    }
+
+    pub fn workspace(&self) -> &Workspace {
+        &self.workspace
+    }
+
+    /// Returns the current trust of the workspace, which may differ from the trust it was opened with.
+    pub fn workspace_trust(&self) -> WorkspaceTrust {
+        if self.workspace_trusted.load(Ordering::Relaxed) {
+            WorkspaceTrust::Trusted
+        } else {
+            WorkspaceTrust::Untrusted
+        }
+    }
+
+    /// Changes the trust of the workspace and re-reads the workspace and workspace folder settings,
+    /// so the settings only allowed for trusted sources are applied or dropped.
+    pub fn set_workspace_trust(
+        &self,
+        ctx: &mut Context,
+        trust: WorkspaceTrust,
+    ) -> Result<ConfigurationChangeEvent> {
+        self.workspace_trusted
+            .store(trust.is_trusted(), Ordering::Relaxed);
+        for parser in &self.workspace_parsers {
+            parser.set_restricted_mode(!trust.is_trusted());
         }
+
+        let mut diff = self.load_workspace_configuration(ctx)?;
+        diff.extend(self.load_folder_configurations(ctx)?);
+
+        Ok(ConfigurationChangeEvent::new(
+            ConfigurationTarget::Workspace,
+            diff,
+        ))
+    }
+
+    /// Returns the value of the attribute for the override identifier, e.g. the language of an editor,
+    /// falling back to the value without the override.
+    pub fn get_value_for_override(
+        &self,
+        attribute_name: &ConfigurationKey,
+        override_ident: Option<&str>,
+    ) -> Option<serde_json::Value> {
+        self.configuration
+            .get_value_for_override(attribute_name, override_ident)
+    }
+
+    /// Returns the value of the attribute for the language and the file of an editor.
+    pub fn get_value_with_overrides(
+        &self,
+        attribute_name: &ConfigurationKey,
+        overrides: &ConfigurationOverrides,
+    ) -> Option<serde_json::Value> {
+        let overrides = ConfigurationOverrides {
+            override_ident: overrides.override_ident.clone(),
+            resource: overrides
+                .resource
+                .as_ref()
+                .map(|resource| std::path::absolute(resource).unwrap_or_else(|_| resource.clone())),
+        };
+
+        self.configuration
+            .get_value_with_overrides(attribute_name, &overrides)
+    }
+
+    /// Returns the value of the attribute for a file, taking the settings of the workspace folder
+    /// that contains it into account.
+    pub fn get_value_for_resource(
+        &self,
+        resource: &Path,
+        attribute_name: &ConfigurationKey,
+    ) -> Option<serde_json::Value> {
+        self.get_value_with_overrides(
+            attribute_name,
+            &ConfigurationOverrides {
+                override_ident: None,
+                resource: Some(resource.to_path_buf()),
+            },
+        )
+    }
+
+    /// Returns the problems found in the settings files, e.g. to show them in a problems list.
+    pub fn diagnostics(&self) -> Vec<ConfigurationDiagnostic> {
+        self.configuration.diagnostics()
+    }
+
+    /// Re-reads the user settings file and returns the settings that have changed since the last load.
+    pub fn reload_user_configuration(&self, ctx: &mut Context) -> Result<ConfigurationChangeEvent> {
+        let user_configuration_model = self
+            .user_configuration
+            .load_configuration(ctx)
+            .context("failed to load user configuration model")?;
+
+        let diff = self
+            .configuration
+            .update_user_configuration(Arc::new(user_configuration_model));
+
+        Ok(ConfigurationChangeEvent::new(
+            ConfigurationTarget::User,
+            diff,
+        ))
+    }
+
+    /// Returns a stream that yields every time the user settings file changes on disk.
+    /// Each item should be followed by a call to `reload_user_configuration`.
+    pub fn watch_user_configuration(
+        &self,
+        ctx: &mut Context,
+    ) -> Result<Pin<Box<dyn Send + Stream<Item = ()>>>> {
+        ctx.block_on_with(self.user_configuration.watch(Duration::from_millis(100)))
+    }
+
+    /// Re-reads the workspace settings file and returns the settings that have changed since the last load.
+    pub fn reload_workspace_configuration(
+        &self,
+        ctx: &mut Context,
+    ) -> Result<ConfigurationChangeEvent> {
+        let diff = self.load_workspace_configuration(ctx)?;
+
+        Ok(ConfigurationChangeEvent::new(
+            ConfigurationTarget::Workspace,
+            diff,
+        ))
+    }
+
+    fn load_workspace_configuration(&self, ctx: &mut Context) -> Result<ConfigurationDifference> {
+        let workspace_configuration_model = match &self.workspace_configuration {
+            Some(workspace_configuration) => workspace_configuration
+                .load_configuration(ctx)
+                .context("failed to load workspace configuration model")?,
+            None => ConfigurationModel::empty(),
+        };
+
+        Ok(self
+            .configuration
+            .update_workspace_configuration(Arc::new(workspace_configuration_model)))
+    }
+
+    /// Returns a stream that yields every time the workspace settings file changes on disk.
+    /// The stream never yields if the workspace has no settings file.
+    /// Each item should be followed by a call to `reload_workspace_configuration`.
+    pub fn watch_workspace_configuration(
+        &self,
+        ctx: &mut Context,
+    ) -> Result<Pin<Box<dyn Send + Stream<Item = ()>>>> {
+        match &self.workspace_configuration {
+            Some(workspace_configuration) => {
+                ctx.block_on_with(workspace_configuration.watch(Duration::from_millis(100)))
+            }
+            None => Ok(Box::pin(futures::stream::pending())),
+        }
+    }
+
+    /// Re-reads the settings files of the workspace folders and returns the settings that have changed since the last load.
+    pub fn reload_folder_configurations(
+        &self,
+        ctx: &mut Context,
+    ) -> Result<ConfigurationChangeEvent> {
+        let diff = self.load_folder_configurations(ctx)?;
+
+        Ok(ConfigurationChangeEvent::new(
+            ConfigurationTarget::WorkspaceFolder,
+            diff,
+        ))
+    }
+
+    fn load_folder_configurations(&self, ctx: &mut Context) -> Result<ConfigurationDifference> {
+        let mut diff = ConfigurationDifference::default();
+        for (folder, folder_configuration) in &self.folder_configurations {
+            let folder_configuration_model = folder_configuration
+                .load_configuration(ctx)
+                .context("failed to load workspace folder configuration model")?;
+
+            let folder_diff = self
+                .configuration
+                .update_folder_configuration(folder.clone(), Arc::new(folder_configuration_model));
+            diff.extend(folder_diff);
+        }
+
+        Ok(diff)
+    }
+
+    /// Returns a stream that yields every time the settings file of any workspace folder changes on disk.
+    /// The stream never yields if the workspace has no folders.
+    /// Each item should be followed by a call to `reload_folder_configurations`.
+    pub fn watch_folder_configurations(
+        &self,
+        ctx: &mut Context,
+    ) -> Result<Pin<Box<dyn Send + Stream<Item = ()>>>> {
+        if self.folder_configurations.is_empty() {
+            return Ok(Box::pin(futures::stream::pending()));
+        }
+
+        let streams = self
+            .folder_configurations
+            .iter()
+            .map(|(_, folder_configuration)| {
+                ctx.block_on_with(folder_configuration.watch(Duration::from_millis(100)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Box::pin(futures::stream::select_all(streams)))
+    }
+
+    /// Sets a value that is kept in memory only, e.g. from a command-line flag or a temporary UI toggle.
+    /// Passing `None` removes the value. Returns the settings whose memory values have changed.
+    pub fn set_memory_value(
+        &self,
+        attribute_name: &ConfigurationKey,
+        value: Option<serde_json::Value>,
+    ) -> ConfigurationChangeEvent {
+        let diff = self.configuration.set_memory_value(attribute_name, value);
+
+        ConfigurationChangeEvent::new(ConfigurationTarget::Memory, diff)
+    }
+
+    /// Removes all values kept in memory and returns the settings that were set.
+    pub fn clear_memory_configuration(&self) -> ConfigurationChangeEvent {
+        let diff = self.configuration.clear_memory_configuration();
+
+        ConfigurationChangeEvent::new(ConfigurationTarget::Memory, diff)
+    }
+
+    /// Searches the registered settings for a settings editor, e.g. `font @modified`.
+    pub fn search_settings(&self, ctx: &mut Context, query: &str) -> Vec<SettingSearchResult> {
+        self.configuration
+            .search(self.registry.read(ctx), &SettingsQuery::parse(query))
+    }
+
+    /// Exports the settings to a bundle that can be imported on another machine or in another profile.
+    pub fn export_settings(
+        &self,
+        ctx: &mut Context,
+        options: &ExportOptions,
+    ) -> ConfigurationBundle {
+        self.configuration.export(self.registry.read(ctx), options)
+    }
+
+    /// Computes the changes importing the bundle would make to the user settings.
+    pub fn preview_import(
+        &self,
+        ctx: &mut Context,
+        bundle: &ConfigurationBundle,
+    ) -> Result<ImportPreview> {
+        let parser =
+            ConfigurationParser::new(self.registry.clone()).with_target(ConfigurationTarget::User);
+
+        ImportPreview::new(
+            ctx,
+            &parser,
+            bundle,
+            self.configuration.get_user_configuration(),
+        )
+    }
+
+    /// Writes the imported settings to the user settings file, resolving every conflict with `resolve`.
+    pub fn import_settings(
+        &self,
+        ctx: &mut Context,
+        preview: &ImportPreview,
+        resolve: impl Fn(&ImportConflict) -> ConflictResolution,
+    ) -> Result<ConfigurationChangeEvent> {
+        for (attribute_name, value) in preview.resolve(resolve) {
+            self.do_update_value(ctx, &attribute_name, &value)?;
+        }
+
+        self.reload_user_configuration(ctx)
+    }
+
+    /// Rewrites the deprecated settings of the user and workspace settings files with the settings
+    /// that replace them. The files are read with the migrated values anyway, this only updates them on disk.
+    pub fn write_back_migrations(&self, ctx: &mut Context) -> Result<()> {
+        let user_migrations = self
+            .configuration
+            .get_user_configuration()
+            .migrated_settings()
+            .clone();
+        if !user_migrations.is_empty() {
+            Self::write_migrations(ctx, &self.configuration_editing, &user_migrations)?;
+            self.reload_user_configuration(ctx)?;
+        }
+
+        if let Some(workspace_configuration) = &self.workspace_configuration {
+            let workspace_migrations = self
+                .configuration
+                .get_workspace_configuration()
+                .migrated_settings()
+                .clone();
+            if !workspace_migrations.is_empty() {
+                let configuration_editing =
+                    ConfigurationEditingService::new(workspace_configuration.resource().clone());
+                Self::write_migrations(ctx, &configuration_editing, &workspace_migrations)?;
+                self.reload_workspace_configuration(ctx)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_migrations(
+        ctx: &mut Context,
+        configuration_editing: &ConfigurationEditingService,
+        migrations: &[MigratedSetting],
+    ) -> Result<()> {
+        for migration in migrations {
+            let new_attribute_name = ConfigurationKey {
+                override_ident: migration.override_ident.clone(),
+                name: migration.new_key.clone(),
+            };
+            let attribute_name = ConfigurationKey {
+                override_ident: migration.override_ident.clone(),
+                name: migration.key.clone(),
+            };
+
+            ctx.block_on_with(
+                configuration_editing.write(&new_attribute_name, Some(migration.value.clone())),
+            )?;
+            ctx.block_on_with(configuration_editing.write(&attribute_name, None))?;
+        }
+
+        Ok(())
+    }
+
+    /// Re-reads the policy file and returns the settings whose policy values have changed.
+    pub fn reload_policy_configuration(
+        &self,
+        ctx: &mut Context,
+    ) -> Result<ConfigurationChangeEvent> {
+        let policy_service = self
+            .policy_provider
+            .load(ctx)
+            .context("failed to load policies")?;
+        let policy_configuration_model = self.configuration_policy.update_policy_service(
+            ctx,
+            &self.default_configuration,
+            policy_service,
+        );
+
+        let diff = self
+            .configuration
+            .update_policy_configuration(policy_configuration_model);
+
+        Ok(ConfigurationChangeEvent::new(
+            ConfigurationTarget::Policy,
+            diff,
+        ))
+    }
+
+    /// Returns a stream that yields every time the policy file changes on disk.
+    /// Each item should be followed by a call to `reload_policy_configuration`.
+    pub fn watch_policy_configuration(
+        &self,
+        ctx: &mut Context,
+    ) -> Result<Pin<Box<dyn Send + Stream<Item = ()>>>> {
+        ctx.block_on_with(self.policy_provider.watch(Duration::from_millis(100)))
+    }
+
+    fn do_update_value(
+        &self,
+        ctx: &mut Context,
+        attribute_name: &ConfigurationKey,
+        value: &serde_json::Value,
+    ) -> Result<()> {
+        let inspected_value = self.configuration.inspect(attribute_name);
+
+        // A policy locks the setting for every override identifier too.
+        let base_name = attribute_name.base();
+        let locked_name = [attribute_name, &base_name]
+            .into_iter()
+            .find(|name| inspected_value.get_policy_value(name).is_some());
+        if let Some(locked_name) = locked_name {
+            return Err(ConfigurationError::PolicyProtected {
+                key: attribute_name.json_path().join("."),
+                policy: inspected_value
+                    .get_policy_name(locked_name)
+                    .unwrap_or_default()
+                    .to_string(),
+            }
+            .into());
+        }
+
+        // A value equal to the default one is removed from the file instead of being written.
+        let value = if inspected_value.get_default_value(attribute_name) == Some(value) {
+            None
+        } else {
+            Some(value.clone())
+        };
+
+        ctx.block_on_with(self.configuration_editing.write(attribute_name, value))
     }
 }
 
-#[async_trait]
 impl AbstractConfigurationService for WorkspaceConfigurationService {
-    fn get_value(&self, attribute_name: AttributeName) -> Option<serde_json::Value> {
+    fn get_value(&self, attribute_name: ConfigurationKey) -> Option<serde_json::Value> {
         self.configuration.get_value(&attribute_name)
     }
 
-    async fn update_value(
+    fn get_section(&self, section: ConfigurationKey) -> Option<serde_json::Value> {
+        self.configuration.get_section(&section)
+    }
+
+    fn update_value(
         &self,
-        attribute_name: AttributeName,
+        ctx: &mut Context,
+        attribute_name: ConfigurationKey,
         value: &serde_json::Value,
     ) -> Result<()> {
-        todo!()
+        // TODO: Check if the setting being changed is a USER level setting
+
+        self.do_update_value(ctx, &attribute_name, value)?;
+        self.reload_user_configuration(ctx)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use platform_configuration::{
+        attribute_name,
+        configuration_migration::ConfigurationMigration,
+        configuration_registry::{
+            ConfigurationNode, ConfigurationNodeType, ConfigurationPropertySchema,
+            ConfigurationScope, PropertyMap, PropertyPolicy,
+        },
+        policy::PolicyDefinitionType,
+        property_key,
+    };
+    use platform_core::context_v2::{AnyContext, ContextCell};
+    use platform_core::platform::cross::client::CrossPlatformClient;
+    use platform_fs::disk::file_system_service::DiskFileSystemService;
+    use platform_workspace::WorkspaceId;
+    use serde_json::json;
+    use std::{fs, rc::Rc};
+
+    /// Creates the service for the settings and the policy files in `dir`.
+    fn create_service(ctx: &mut Context, dir: &Path) -> WorkspaceConfigurationService {
+        create_service_with_folders(ctx, dir, Vec::new()).unwrap()
+    }
+
+    /// Creates the service for a workspace with the folders, see `create_service`.
+    fn create_service_with_folders(
+        ctx: &mut Context,
+        dir: &Path,
+        folders: Vec<String>,
+    ) -> Result<WorkspaceConfigurationService> {
+        let registry = ctx.create_atom(|_| {
+            let mut properties = PropertyMap::new();
+            properties.insert(
+                property_key!(editor.lineHeight),
+                ConfigurationPropertySchema {
+                    typ: Some(ConfigurationNodeType::Number),
+                    default: Some(json!(20)),
+                    policy: Some(PropertyPolicy {
+                        name: "editorLineHeightPolicy".to_string(),
+                    }),
+                    ..Default::default()
+                },
+            );
+            properties.insert(
+                property_key!(editor.fontSize),
+                ConfigurationPropertySchema {
+                    typ: Some(ConfigurationNodeType::Number),
+                    default: Some(json!(12)),
+                    ..Default::default()
+                },
+            );
+            properties.insert(
+                property_key!(editor.formatOnSave),
+                ConfigurationPropertySchema {
+                    scope: Some(ConfigurationScope::Resource),
+                    typ: Some(ConfigurationNodeType::Bool),
+                    default: Some(json!(false)),
+                    ..Default::default()
+                },
+            );
+
+            let mut registry = ConfigurationRegistry::new();
+            registry.register_configuration(&ConfigurationNode {
+                id: "editor".to_string(),
+                scope: None,
+                order: None,
+                typ: None,
+                title: None,
+                description: None,
+                properties: Some(properties),
+                parent_of: None,
+                source: None,
+            });
+            registry.register_migration(ConfigurationMigration::rename(
+                "editor.size",
+                "editor.fontSize",
+            ));
+
+            registry
+        });
+
+        let fs_service = Arc::new(DiskFileSystemService::new());
+        let policy_provider = FilePolicyProvider::new(
+            dir.join("policies.json"),
+            [(
+                "editorLineHeightPolicy".to_string(),
+                PolicyDefinitionType::Number,
+            )]
+            .into_iter()
+            .collect(),
+            fs_service.clone(),
+        );
+        let workspace = Workspace {
+            id: WorkspaceId::Empty,
+            folders,
+            configuration_uri: None,
+            trust: WorkspaceTrust::Trusted,
+        };
+
+        WorkspaceConfigurationService::new(
+            ctx,
+            workspace,
+            registry,
+            ConfigurationPlatform::current(),
+            policy_provider,
+            dir.join("settings.json"),
+            fs_service,
+        )
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "moss_configuration_service_{}_{}",
+            name,
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn test_update_value_rejects_policy_locked_override() {
+        let dir = temp_dir("policy");
+        fs::write(
+            dir.join("policies.json"),
+            r#"{ "editorLineHeightPolicy": 45 }"#,
+        )
+        .unwrap();
+        fs::write(dir.join("settings.json"), "{}").unwrap();
+
+        let ctx_cell = ContextCell::new(Rc::new(CrossPlatformClient::new()));
+        let ctx: &mut Context = &mut ctx_cell.borrow_mut();
+        let service = create_service(ctx, &dir);
+
+        for attribute_name in [
+            attribute_name!(editor.lineHeight),
+            attribute_name!([rust].editor.lineHeight),
+        ] {
+            let err = service
+                .update_value(ctx, attribute_name, &json!(30))
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<ConfigurationError>(),
+                Some(ConfigurationError::PolicyProtected { policy, .. })
+                    if policy == "editorLineHeightPolicy"
+            ));
+        }
+
+        service
+            .update_value(ctx, attribute_name!([rust].editor.fontSize), &json!(14))
+            .unwrap();
+
+        let content = fs::read_to_string(dir.join("settings.json")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!content.contains("editor.lineHeight"));
+        assert_eq!(
+            service.get_value(attribute_name!([rust].editor.lineHeight)),
+            Some(json!(45))
+        );
+    }
+
+    #[test]
+    fn test_write_back_migrations() {
+        let dir = temp_dir("migrations");
+        fs::write(dir.join("policies.json"), "{}").unwrap();
+        fs::write(dir.join("settings.json"), "{\n  \"editor.size\": 16\n}").unwrap();
+
+        let ctx_cell = ContextCell::new(Rc::new(CrossPlatformClient::new()));
+        let ctx: &mut Context = &mut ctx_cell.borrow_mut();
+        let service = create_service(ctx, &dir);
+
+        assert_eq!(
+            service.get_value(attribute_name!(editor.fontSize)),
+            Some(json!(16))
+        );
+
+        service.write_back_migrations(ctx).unwrap();
+
+        let content = fs::read_to_string(dir.join("settings.json")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!content.contains("editor.size"));
+        assert!(content.contains("\"editor.fontSize\": 16"));
+        assert_eq!(
+            service.get_value(attribute_name!(editor.fontSize)),
+            Some(json!(16))
+        );
+        assert!(service
+            .configuration
+            .get_user_configuration()
+            .migrated_settings()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_folder_settings_with_property_scope() {
+        let dir = temp_dir("folders");
+        let folder = dir.join("project");
+        fs::create_dir_all(folder.join(".moss")).unwrap();
+        fs::write(dir.join("policies.json"), "{}").unwrap();
+        fs::write(dir.join("settings.json"), "{}").unwrap();
+        fs::write(
+            folder.join(".moss").join("settings.json"),
+            r#"{ "editor.formatOnSave": true, "editor.fontSize": 20 }"#,
+        )
+        .unwrap();
+
+        let ctx_cell = ContextCell::new(Rc::new(CrossPlatformClient::new()));
+        let ctx: &mut Context = &mut ctx_cell.borrow_mut();
+        let service =
+            create_service_with_folders(ctx, &dir, vec![folder.to_string_lossy().into_owned()])
+                .unwrap();
+        let diagnostics = service.diagnostics();
+        fs::remove_dir_all(&dir).unwrap();
+
+        // `editor.formatOnSave` is registered with the Resource scope by a node without a scope.
+        assert_eq!(
+            service.get_value_for_resource(
+                &folder.join("main.rs"),
+                &attribute_name!(editor.formatOnSave)
+            ),
+            Some(json!(true))
+        );
+        assert_eq!(
+            service.get_value_for_resource(
+                &dir.join("other").join("main.rs"),
+                &attribute_name!(editor.formatOnSave)
+            ),
+            Some(json!(false))
+        );
+        assert_eq!(
+            service
+                .get_value_for_resource(&folder.join("main.rs"), &attribute_name!(editor.fontSize)),
+            Some(json!(12))
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "Setting `editor.fontSize` has Window scope and can't be set in workspace folder settings"
+        );
+    }
+
+    #[test]
+    fn test_unreadable_settings_file_is_an_error() {
+        let dir = temp_dir("unreadable");
+        let folder = dir.join("project");
+        fs::create_dir_all(folder.join(".moss")).unwrap();
+        fs::write(dir.join("settings.json"), "{}").unwrap();
+        fs::write(folder.join(".moss").join("settings.json"), [0xff, 0xfe]).unwrap();
+
+        let ctx_cell = ContextCell::new(Rc::new(CrossPlatformClient::new()));
+        let ctx: &mut Context = &mut ctx_cell.borrow_mut();
+        let result =
+            create_service_with_folders(ctx, &dir, vec![folder.to_string_lossy().into_owned()]);
+
+        fs::write(folder.join(".moss").join("settings.json"), "{}").unwrap();
+        fs::write(dir.join("policies.json"), [0xff, 0xfe]).unwrap();
+        let policy_result = create_service_with_folders(ctx, &dir, Vec::new());
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "failed to load workspace folder configuration model"
+        );
+        assert_eq!(
+            policy_result.err().unwrap().to_string(),
+            "failed to load policies"
+        );
     }
 }
@@ -1,7 +1 @@
 pub mod configuration_service;
-
-#[macro_use]
-extern crate anyhow;
-
-#[macro_use]
-extern crate async_trait;
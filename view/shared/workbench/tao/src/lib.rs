@@ -1,20 +1,26 @@
-pub mod contribution;
 pub mod window;
 
 use std::{
     any::Any,
     cell::{Cell, RefCell},
-    path::PathBuf,
+    path::{Path, PathBuf},
     rc::Rc,
     sync::Arc,
 };
 
 use anyhow::Result;
-use contribution::WORKBENCH_TAO_WINDOW;
+use futures::StreamExt;
 use once_cell::unsync::OnceCell;
 use platform_configuration::{
-    attribute_name, configuration_policy::ConfigurationPolicyService,
-    configuration_registry::ConfigurationRegistry, AbstractConfigurationService,
+    attribute_name,
+    configuration_contribution::ConfigurationContributions,
+    configuration_model::{ConfigurationChangeEvent, ConfigurationTarget},
+    configuration_platform::ConfigurationPlatform,
+    configuration_registry::{ConfigurationNode, ConfigurationRegistry},
+    configuration_search::SettingSearchResult,
+    policy_file::FilePolicyProvider,
+    typed_settings::TypedConfigurationService,
+    AbstractConfigurationService,
 };
 use platform_core::context_v2::{
     async_context::AsyncContext, atom::Atom, node::AnyNodeValue, subscription::Subscription,
@@ -25,9 +31,12 @@ use platform_fs::disk::file_system_service::{
     AbstractDiskFileSystemService, DiskFileSystemService,
 };
 use platform_user_profile::user_profile_service::UserProfileService as PlatformUserProfileService;
-use platform_workspace::{Workspace, WorkspaceId};
+use platform_workspace::{Workspace, WorkspaceId, WorkspaceTrust};
 use specta::Type;
-use tauri::{AppHandle, Emitter, WebviewWindow};
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+use tracing::warn;
+use window::NativePlatformInfo;
+use workbench_contribution::WindowSettings;
 use workbench_service_configuration_tao::configuration_service::WorkspaceConfigurationService;
 use workbench_service_environment_tao::environment_service::NativeEnvironmentService;
 use workbench_service_user_profile_tao::user_profile_service::UserProfileService;
@@ -38,8 +47,8 @@ extern crate serde;
 #[macro_use]
 extern crate anyhow;
 
-#[macro_use]
-extern crate lazy_static;
+/// Directory of the user configuration with the manifests of the settings contributed by plugins and teams.
+const CONFIGURATION_CONTRIBUTIONS_DIR: &str = "contributions";
 
 // TODO: this will be removed after testing is
 #[derive(Clone)]
@@ -63,6 +72,22 @@ impl MockFontSizeService {
     }
 }
 
+/// Holds the last configuration change, so that observers can react to it.
+#[derive(Clone)]
+struct ConfigurationChangeState {
+    last_event: Option<ConfigurationChangeEvent>,
+}
+
+impl AnyNodeValue for ConfigurationChangeState {
+    fn as_any_ref(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
 #[derive(Debug, Type, Serialize)]
 pub enum WorkbenchState {
     Empty,
@@ -73,9 +98,13 @@ pub struct Workbench {
     workspace_id: WorkspaceId,
     service_registry: Rc<RefCell<ServiceRegistry>>,
     configuration_registry: Atom<ConfigurationRegistry>,
+    /// The platform the default values of the settings are resolved for.
+    platform: ConfigurationPlatform,
     // TODO: this will be removed after testing is complete
     font_size_service: Atom<MockFontSizeService>,
     _observe_font_size_service: OnceCell<Subscription>,
+    configuration_change: Atom<ConfigurationChangeState>,
+    _observe_configuration_change: OnceCell<Subscription>,
     tao_handle: OnceCell<Rc<AppHandle>>,
 }
 
@@ -87,12 +116,31 @@ impl Workbench {
         ctx: &mut AsyncContext,
         service_registry: ServiceRegistry,
         workspace_id: WorkspaceId,
+        platform_info: &NativePlatformInfo,
     ) -> Result<Self> {
+        // Contributions are registered together with the built-in settings,
+        // so they are known before any settings file is read.
+        let contributed_nodes = Self::load_contributions(
+            &service_registry
+                .get_unchecked::<NativeEnvironmentService>()
+                .user_home_dir()
+                .join(".config")
+                .join("moss")
+                .join(CONFIGURATION_CONTRIBUTIONS_DIR),
+        );
+
         let configuration_registry = ctx.apply(move |tx_ctx| {
             let configuration_registry = tx_ctx.create_atom(|_| ConfigurationRegistry::new());
 
             tx_ctx.update_atom(&configuration_registry, |this, ctx| {
-                this.register_configuration(&WORKBENCH_TAO_WINDOW);
+                for node in workbench_contribution::configuration_nodes() {
+                    this.register_configuration(node);
+                }
+                for node in &contributed_nodes {
+                    for diagnostic in this.register_contribution(node) {
+                        warn!("skipped a contributed setting: {diagnostic}");
+                    }
+                }
 
                 ctx.notify();
             });
@@ -108,12 +156,19 @@ impl Workbench {
             font_service_atom
         })?;
 
+        let configuration_change = ctx.apply(|tx_ctx| {
+            tx_ctx.create_atom(|_ctx| ConfigurationChangeState { last_event: None })
+        })?;
+
         Ok(Self {
             workspace_id,
             service_registry: Rc::new(RefCell::new(service_registry)),
             configuration_registry,
+            platform: ConfigurationPlatform::from_os_name(&platform_info.os),
             font_size_service: font_service_atom,
             _observe_font_size_service: OnceCell::new(),
+            configuration_change,
+            _observe_configuration_change: OnceCell::new(),
             tao_handle: OnceCell::new(),
         })
     }
@@ -125,6 +180,7 @@ impl Workbench {
         // let ctx: &mut Context = &mut cell.as_ref().borrow_mut();
 
         ctx.apply(|cx| self.initialize_services(cx))??;
+        self.watch_configuration(ctx)?;
 
         let service_registry = self.service_registry.as_ref().borrow();
         let config_service = service_registry.get_unchecked::<WorkspaceConfigurationService>();
@@ -138,28 +194,17 @@ impl Workbench {
     fn initialize_services(&self, ctx: &mut Context) -> Result<()> {
         let workspace = self.restore_workspace();
 
-        let configuration_policy_service = ConfigurationPolicyService {
-            definitions: {
-                use platform_configuration::policy::PolicyDefinitionType;
+        let policy_definitions = {
+            use platform_configuration::policy::PolicyDefinitionType;
 
-                let mut this = hashbrown::HashMap::new();
+            let mut this = hashbrown::HashMap::new();
 
-                this.insert(
-                    "editorLineHeightPolicy".to_string(),
-                    PolicyDefinitionType::Number,
-                );
+            this.insert(
+                "editorLineHeightPolicy".to_string(),
+                PolicyDefinitionType::Number,
+            );
 
-                this
-            },
-            policies: {
-                let mut this = hashbrown::HashMap::new();
-                this.insert(
-                    "editorLineHeightPolicy".to_string(),
-                    serde_json::Value::Number(serde_json::Number::from(45)),
-                );
-
-                this
-            },
+            this
         };
 
         let mut service_registry = self.service_registry.as_ref().borrow_mut();
@@ -167,6 +212,12 @@ impl Workbench {
         let fs_service = service_registry.get_unchecked::<Arc<DiskFileSystemService>>();
         let environment_service = service_registry.get_unchecked::<NativeEnvironmentService>();
 
+        let policy_provider = FilePolicyProvider::new(
+            FilePolicyProvider::default_resource(),
+            policy_definitions,
+            Arc::clone(&fs_service) as Arc<dyn AbstractDiskFileSystemService>,
+        );
+
         let user_profile_service = ctx.block_on_with({
             UserProfileService::new(
                 environment_service.user_home_dir().clone(),
@@ -184,25 +235,132 @@ impl Workbench {
             ctx,
             workspace,
             self.configuration_registry.clone(),
-            configuration_policy_service,
+            self.platform,
+            policy_provider,
             user_profile_service
                 .default_profile()
                 .settings_resource
                 .clone(),
             Arc::clone(&fs_service) as Arc<dyn AbstractDiskFileSystemService>,
-        );
+        )?;
 
         service_registry.insert(workspace_configuration_service);
 
         Ok(())
     }
 
+    /// Loads the settings contributed by the manifests of the directory, see `ConfigurationContributions`.
+    /// A broken manifest is skipped, the other contributions and the built-in settings are still registered.
+    fn load_contributions(dir: &Path) -> Vec<ConfigurationNode> {
+        match ConfigurationContributions::load(dir) {
+            Ok(contributions) => {
+                for diagnostic in &contributions.diagnostics {
+                    warn!("skipped a configuration contribution: {diagnostic}");
+                }
+
+                contributions.nodes
+            }
+            Err(err) => {
+                warn!("failed to load the configuration contributions: {err:#}");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Reloads the user, workspace and workspace folder settings and the policies whenever they change on disk and notifies the observers of `configuration_change`.
+    /// A failure to watch is not fatal: the settings are still read at startup and written through the service.
+    fn watch_configuration(&self, ctx: &mut AsyncContext) -> Result<()> {
+        let (user_changes, workspace_changes, folder_changes, policy_changes) =
+            ctx.apply(|cx| {
+                let service_registry = self.service_registry.as_ref().borrow();
+                let config_service =
+                    service_registry.get_unchecked::<WorkspaceConfigurationService>();
+
+                (
+                    config_service.watch_user_configuration(cx),
+                    config_service.watch_workspace_configuration(cx),
+                    config_service.watch_folder_configurations(cx),
+                    config_service.watch_policy_configuration(cx),
+                )
+            })?;
+
+        let mut sources = Vec::new();
+        match user_changes {
+            Ok(changes) => sources.push(changes.map(|_| ConfigurationTarget::User).boxed()),
+            Err(err) => warn!("failed to watch the user settings: {err:#}"),
+        }
+        match workspace_changes {
+            Ok(changes) => sources.push(changes.map(|_| ConfigurationTarget::Workspace).boxed()),
+            Err(err) => warn!("failed to watch the workspace settings: {err:#}"),
+        }
+        match folder_changes {
+            Ok(changes) => sources.push(
+                changes
+                    .map(|_| ConfigurationTarget::WorkspaceFolder)
+                    .boxed(),
+            ),
+            Err(err) => warn!("failed to watch the workspace folder settings: {err:#}"),
+        }
+        match policy_changes {
+            Ok(changes) => sources.push(changes.map(|_| ConfigurationTarget::Policy).boxed()),
+            Err(err) => warn!("failed to watch the policies: {err:#}"),
+        }
+
+        if sources.is_empty() {
+            return Ok(());
+        }
+
+        let mut changes = futures::stream::select_all(sources);
+
+        let service_registry = Rc::clone(&self.service_registry);
+        let configuration_change = self.configuration_change.clone();
+
+        ctx.spawn_local(|async_ctx| async move {
+            while let Some(target) = changes.next().await {
+                let result = async_ctx.apply(|tx_ctx| {
+                    let service_registry = service_registry.as_ref().borrow();
+                    let config_service =
+                        service_registry.get_unchecked::<WorkspaceConfigurationService>();
+
+                    let event = match target {
+                        ConfigurationTarget::Policy => {
+                            config_service.reload_policy_configuration(tx_ctx)?
+                        }
+                        ConfigurationTarget::Workspace => {
+                            config_service.reload_workspace_configuration(tx_ctx)?
+                        }
+                        ConfigurationTarget::WorkspaceFolder => {
+                            config_service.reload_folder_configurations(tx_ctx)?
+                        }
+                        _ => config_service.reload_user_configuration(tx_ctx)?,
+                    };
+                    if !event.is_empty() {
+                        tx_ctx.update_atom(&configuration_change, |this, ctx| {
+                            this.last_event = Some(event);
+                            ctx.notify();
+                        });
+                    }
+
+                    Ok::<_, anyhow::Error>(())
+                });
+
+                if let Err(err) = result.and_then(|result| result) {
+                    warn!("failed to reload the {target:?} configuration: {err:#}");
+                }
+            }
+        })
+        .detach();
+
+        Ok(())
+    }
+
     fn restore_workspace(&self) -> Workspace {
         match &self.workspace_id {
             WorkspaceId::Empty => Workspace {
                 id: WorkspaceId::Empty,
                 folders: vec![],
                 configuration_uri: None,
+                trust: WorkspaceTrust::Trusted,
             },
             WorkspaceId::Some(_id) => {
                 struct SimpleWorkspaceData {
@@ -219,6 +377,8 @@ impl Workbench {
                     id: self.workspace_id.clone(),
                     folders: vec![],
                     configuration_uri: Some(mock_workspace_data.path),
+                    // Projects are opened in restricted mode until the user trusts them.
+                    trust: WorkspaceTrust::Untrusted,
                 }
             }
         }
@@ -237,36 +397,58 @@ impl Workbench {
                     .unwrap();
             },
         ));
+
+        let tao_handle_clone = Rc::clone(self.tao_handle.get().unwrap());
+        let service_registry = Rc::clone(&self.service_registry);
+
+        let _ = self._observe_configuration_change.set(ctx.observe(
+            &self.configuration_change,
+            move |this, cx| {
+                let Some(event) = this.read(cx).last_event.clone() else {
+                    return;
+                };
+
+                if let Err(err) =
+                    tao_handle_clone.emit("configuration-change-event", event.affected_keys())
+                {
+                    warn!("failed to emit the configuration change event: {err}");
+                }
+
+                if event.affects_configuration("window") {
+                    if let Some(window) = tao_handle_clone.get_webview_window("main") {
+                        let service_registry = service_registry.as_ref().borrow();
+                        let config_service =
+                            service_registry.get_unchecked::<WorkspaceConfigurationService>();
+
+                        if let Err(err) = Self::apply_window_size(config_service, &window) {
+                            warn!("failed to apply the window size: {err:#}");
+                        }
+                    }
+                }
+            },
+        ));
     }
 
     pub fn set_configuration_window_size(&self, window: WebviewWindow) -> Result<()> {
-        use tauri::{LogicalSize, Size::Logical};
-
         let service_registry = self.service_registry.as_ref().borrow();
         let config_service = service_registry.get_unchecked::<WorkspaceConfigurationService>();
 
-        let width_value = config_service
-            .get_value(attribute_name!(window.defaultWidth))
-            .expect(
-                "The default window width size must be set in the workbench configuration schema",
-            )
-            .as_i64()
-            .expect("The default window width size must be a number");
+        Self::apply_window_size(config_service, &window)
+    }
+
+    fn apply_window_size(
+        config_service: &WorkspaceConfigurationService,
+        window: &WebviewWindow,
+    ) -> Result<()> {
+        use tauri::{LogicalSize, Size::Logical};
+
+        let settings = config_service.get_settings::<WindowSettings>()?;
+
+        window.set_size(Logical(LogicalSize {
+            width: settings.default_width as f64,
+            height: settings.default_height as f64,
+        }))?;
 
-        let height_value = config_service
-            .get_value(attribute_name!(window.defaultHeight))
-            .expect(
-                "The default window height size must be set in the workbench configuration schema",
-            )
-            .as_i64()
-            .expect("The default window height size must be a number");
-
-        window
-            .set_size(Logical(LogicalSize {
-                width: width_value as f64,
-                height: height_value as f64,
-            }))
-            .unwrap();
         Ok(())
     }
 
@@ -284,4 +466,19 @@ impl<'a> Workbench {
             })
         })
     }
+
+    /// Searches the settings for the settings editor, the query may contain filters
+    /// like `@modified`, `@deprecated` or `@tag:experimental`.
+    pub fn search_settings(
+        &self,
+        ctx: &AsyncContext,
+        query: &str,
+    ) -> Result<Vec<SettingSearchResult>> {
+        ctx.apply(|tx_ctx| {
+            let service_registry = self.service_registry.as_ref().borrow();
+            let config_service = service_registry.get_unchecked::<WorkspaceConfigurationService>();
+
+            config_service.search_settings(tx_ctx, query)
+        })
+    }
 }